@@ -13,16 +13,16 @@ pub trait Bool: Debug + Default + Clone + Copy + private_bool::Sealed {}
 impl Bool for True {}
 impl Bool for False {}
 
-impl Into<bool> for True {
+impl From<True> for bool {
     #[inline(always)]
-    fn into(self) -> bool {
+    fn from(_: True) -> bool {
         true
     }
 }
 
-impl Into<bool> for False {
+impl From<False> for bool {
     #[inline(always)]
-    fn into(self) -> bool {
+    fn from(_: False) -> bool {
         false
     }
 }
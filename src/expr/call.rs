@@ -0,0 +1,127 @@
+use crate::expr::cond::Branch;
+use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::env::EvalWith;
+use crate::expr::ops::Mul;
+use crate::expr::subst::{Subst, SubstOut};
+use crate::expr::{Expr, Expression, Reduce, Reduced};
+use crate::func::Func;
+
+/// A user-defined function with a known derivative. The function itself is given by implementing
+/// [`Func`] for the values it's applied to, and its derivative is another such function, which
+/// can be `Differentiable` again for higher derivatives.
+///
+/// [`Func`]: ../../func/trait.Func.html
+pub trait Differentiable {
+    type Deriv: Default;
+}
+
+pub type DerivFn<F> = <F as Differentiable>::Deriv;
+
+/// Application of the function `F` to an expression, see [`Expr::apply`].
+///
+/// [`Expr::apply`]: ../struct.Expr.html#method.apply
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Call<F, T>(pub F, pub T);
+
+impl<T> Expr<T> {
+    /// Apply a user-defined function, which is differentiated by the chain rule with its
+    /// [`Differentiable::Deriv`](call/trait.Differentiable.html).
+    /// # Examples
+    /// ```
+    /// use typing::impl_func;
+    /// use typing::expr::call::Differentiable;
+    /// use typing::expr::var::var;
+    /// use typing::tags::X;
+    ///
+    /// // softplus(x) = ln(1 + e^x), whose derivative is the logistic function
+    /// #[derive(Copy, Clone, Debug, Default)]
+    /// struct Softplus;
+    /// #[derive(Copy, Clone, Debug, Default)]
+    /// struct Logistic;
+    /// #[derive(Copy, Clone, Debug, Default)]
+    /// struct LogisticDeriv;
+    ///
+    /// impl_func!(for Softplus { |x: f64| -> f64 { x.exp().ln_1p() } });
+    /// impl_func!(for Logistic { |x: f64| -> f64 { 1.0 / (1.0 + (-x).exp()) } });
+    /// impl_func!(for LogisticDeriv { |x: f64| -> f64 { 0.25 / (x / 2.0).cosh().powi(2) } });
+    ///
+    /// impl Differentiable for Softplus {
+    ///     type Deriv = Logistic;
+    /// }
+    ///
+    /// impl Differentiable for Logistic {
+    ///     type Deriv = LogisticDeriv;
+    /// }
+    ///
+    /// let x = var(0.0, X);
+    /// let f = (x * 2.0).apply::<Softplus>();
+    /// assert_eq!(f.eval(), 2f64.ln());
+    /// assert_eq!(f.deriv(X).eval(), 1.0);
+    /// assert_eq!(f.deriv(X).deriv(X).eval(), 1.0);
+    /// assert_eq!(f.deriv(X).to_string(), "Logistic(X * 2) * 2");
+    /// ```
+    #[inline]
+    pub fn apply<F: Default>(self) -> Expr<Call<F, T>> {
+        Expr(Call(F::default(), self.0))
+    }
+}
+
+impl<F, T> Expression for Call<F, T>
+where
+    F: Func<T::Output>,
+    T: Expression,
+{
+    type Output = F::Output;
+
+    #[inline(always)]
+    fn eval(self) -> Self::Output {
+        F::call(self.1.eval())
+    }
+}
+
+impl<F, T, Env> EvalWith<Env> for Call<F, T>
+where
+    F: Func<T::Output>,
+    T: EvalWith<Env>,
+{
+    type Output = F::Output;
+
+    #[inline(always)]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        F::call(self.1.eval_with(env))
+    }
+}
+
+impl<F, T: Subst<Tag, R>, Tag, R> Subst<Tag, R> for Call<F, T> {
+    type Output = Call<F, SubstOut<T, Tag, R>>;
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        Call(self.0, self.1.subst(replacement))
+    }
+}
+
+// the function is opaque, so it isn't folded even if the argument is a constant
+impl<F, T: Branch> Reduce for Call<F, T> {
+    type Output = Call<F, T::Output>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Call(self.0, self.1.branch())
+    }
+}
+
+// d/dx F(g) = F'(g) g'
+impl<Tag, F, T> Deriv<Tag> for Call<F, T>
+where
+    F: Differentiable,
+    T: Deriv<Tag> + Clone,
+    Mul<Call<DerivFn<F>, T>, Dv<T, Tag>>: Reduce,
+{
+    type Output = Reduced<Mul<Call<DerivFn<F>, T>, Dv<T, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Mul(Call(Default::default(), self.1.clone()), self.1.deriv()).reduce()
+    }
+}
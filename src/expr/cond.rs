@@ -0,0 +1,277 @@
+use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::env::EvalWith;
+use crate::expr::ops::Neg;
+use crate::expr::subst::{Subst, SubstOut};
+use crate::expr::{Expr, Expression, One, Reduce, Reduced, Zero};
+
+macro_rules! expr_compare {
+    ($($(#[$meta:meta])* $name:ident, $method:ident, $trait:ident::$op:ident;)*) => {$(
+        $(#[$meta])*
+        #[derive(Copy, Clone, Default, Debug)]
+        pub struct $name<L, R>(pub L, pub R);
+
+        impl<L> Expr<L> {
+            #[inline]
+            pub fn $method<R>(self, rhs: Expr<R>) -> Expr<$name<L, R>> {
+                Expr($name(self.0, rhs.0))
+            }
+        }
+
+        impl<L, R> Expression for $name<L, R>
+        where
+            L: Expression,
+            R: Expression,
+            L::Output: $trait<R::Output>,
+        {
+            type Output = bool;
+
+            #[inline(always)]
+            fn eval(self) -> Self::Output {
+                self.0.eval().$op(&self.1.eval())
+            }
+        }
+
+        impl<L, R, Env> EvalWith<Env> for $name<L, R>
+        where
+            L: EvalWith<Env>,
+            R: EvalWith<Env>,
+            L::Output: $trait<R::Output>,
+        {
+            type Output = bool;
+
+            #[inline(always)]
+            fn eval_with(&self, env: &Env) -> Self::Output {
+                self.0.eval_with(env).$op(&self.1.eval_with(env))
+            }
+        }
+
+        impl<L, R, Tag, X> Subst<Tag, X> for $name<L, R>
+        where
+            L: Subst<Tag, X>,
+            R: Subst<Tag, X>,
+        {
+            type Output = $name<SubstOut<L, Tag, X>, SubstOut<R, Tag, X>>;
+
+            #[inline]
+            fn subst(self, replacement: &X) -> Self::Output {
+                $name(self.0.subst(replacement), self.1.subst(replacement))
+            }
+        }
+
+        impl<L: Branch, R: Branch> Reduce for $name<L, R> {
+            type Output = $name<L::Output, R::Output>;
+
+            #[inline]
+            fn reduce(self) -> Self::Output {
+                $name(self.0.branch(), self.1.branch())
+            }
+        }
+    )*};
+}
+
+expr_compare!(
+    /// `l < r`
+    Less, lt, PartialOrd::lt;
+    /// `l <= r`
+    LessEq, le, PartialOrd::le;
+    /// `l > r`
+    Greater, gt, PartialOrd::gt;
+    /// `l >= r`
+    GreaterEq, ge, PartialOrd::ge;
+    /// `l == r`
+    Equal, eq, PartialEq::eq;
+    /// `l != r`
+    NotEqual, ne, PartialEq::ne;
+);
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Choice between two expressions of the same type by a condition, which only evaluates the chosen
+/// one. Its derivative is the derivative of the chosen expression.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Select<C, A, B>(pub C, pub A, pub B);
+
+/// Create an expression evaluating to `a` if `cond` is true and to `b` otherwise.
+/// # Examples
+/// ```
+/// use typing::expr::cond::select;
+/// use typing::expr::constant::constant;
+/// use typing::expr::var::var;
+/// use typing::tags::V;
+///
+/// // coulomb friction with a linear region around zero velocity
+/// let friction = |v: f64| {
+///     let v = var(v, V);
+///     select(v.abs().lt(constant(0.1)), v * 5.0, v.abs() / v * 0.5)
+/// };
+/// assert_eq!(friction(0.05).eval(), 0.25);
+/// assert_eq!(friction(-2.0).eval(), -0.5);
+/// assert_eq!(friction(0.05).deriv(V).eval(), 5.0);
+/// assert_eq!(friction(-2.0).deriv(V).eval(), 0.0);
+/// ```
+#[inline]
+pub fn select<C, A, B>(cond: Expr<C>, a: Expr<A>, b: Expr<B>) -> Expr<Select<C, A, B>> {
+    Expr(Select(cond.0, a.0, b.0))
+}
+
+impl<C, A, B> Expression for Select<C, A, B>
+where
+    C: Expression,
+    C::Output: Into<bool>,
+    A: Expression,
+    B: Expression<Output = A::Output>,
+{
+    type Output = A::Output;
+
+    #[inline(always)]
+    fn eval(self) -> Self::Output {
+        if self.0.eval().into() {
+            self.1.eval()
+        } else {
+            self.2.eval()
+        }
+    }
+}
+
+impl<C, A, B, Env> EvalWith<Env> for Select<C, A, B>
+where
+    C: EvalWith<Env>,
+    C::Output: Into<bool>,
+    A: EvalWith<Env>,
+    B: EvalWith<Env, Output = A::Output>,
+{
+    type Output = A::Output;
+
+    #[inline(always)]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        if self.0.eval_with(env).into() {
+            self.1.eval_with(env)
+        } else {
+            self.2.eval_with(env)
+        }
+    }
+}
+
+impl<C, A, B, Tag, X> Subst<Tag, X> for Select<C, A, B>
+where
+    C: Subst<Tag, X>,
+    A: Subst<Tag, X>,
+    B: Subst<Tag, X>,
+{
+    type Output = Select<SubstOut<C, Tag, X>, SubstOut<A, Tag, X>, SubstOut<B, Tag, X>>;
+
+    #[inline]
+    fn subst(self, replacement: &X) -> Self::Output {
+        Select(
+            self.0.subst(replacement),
+            self.1.subst(replacement),
+            self.2.subst(replacement),
+        )
+    }
+}
+
+/// Reduction of the operands of comparisons and the branches of a [`Select`](struct.Select.html),
+/// which may be the constants `Zero` and `One`, e.g. from differentiation.
+#[doc(hidden)]
+pub trait Branch {
+    type Output;
+    fn branch(self) -> Self::Output;
+}
+
+impl<T: Reduce> Branch for T {
+    type Output = Reduced<T>;
+
+    #[inline(always)]
+    fn branch(self) -> Self::Output {
+        self.reduce()
+    }
+}
+
+impl<T> Branch for Zero<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn branch(self) -> Self::Output {
+        self
+    }
+}
+
+impl<T> Branch for One<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn branch(self) -> Self::Output {
+        self
+    }
+}
+
+impl<C: Reduce, A: Branch, B: Branch> Reduce for Select<C, A, B> {
+    type Output = Select<Reduced<C>, A::Output, B::Output>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Select(self.0.reduce(), self.1.branch(), self.2.branch())
+    }
+}
+
+// the derivative is piecewise, so it's undefined where the condition switches
+impl<C, A, B, Tag> Deriv<Tag> for Select<C, A, B>
+where
+    C: Clone,
+    A: Deriv<Tag>,
+    B: Deriv<Tag>,
+    Select<C, Dv<A, Tag>, Dv<B, Tag>>: Reduce,
+{
+    type Output = Reduced<Select<C, Dv<A, Tag>, Dv<B, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Select(self.0.clone(), self.1.deriv(), self.2.deriv()).reduce()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T: Clone> Expr<T> {
+    /// Absolute value, as `x` or `-x` whichever is greater.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::tags::X;
+    ///
+    /// let x = var(-3.0, X);
+    /// assert_eq!(x.abs().eval(), 3.0);
+    /// assert_eq!(x.abs().deriv(X).eval(), -1.0);
+    /// ```
+    #[inline]
+    #[allow(clippy::type_complexity)]
+    pub fn abs(self) -> Expr<Select<Less<T, Neg<T>>, Neg<T>, T>> {
+        let x = self.0;
+        Expr(Select(Less(x.clone(), Neg(x.clone())), Neg(x.clone()), x))
+    }
+
+    /// The lesser of two expressions, which is `rhs` if they are equal.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::paren;
+    /// use typing::expr::Expression;
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(1.0, X);
+    /// let y = var(2.0, Y);
+    /// assert_eq!(x.min(y * 3.0).eval(), 1.0);
+    /// assert_eq!(x.max(y * 3.0).eval(), 6.0);
+    /// assert_eq!(x.max(y * 3.0).grad(paren!(X, Y)).eval(), paren!(0.0, 3.0));
+    /// ```
+    #[inline]
+    pub fn min<R: Clone>(self, rhs: Expr<R>) -> Expr<Select<Less<T, R>, T, R>> {
+        Expr(Select(Less(self.0.clone(), rhs.0.clone()), self.0, rhs.0))
+    }
+
+    /// The greater of two expressions, which is `self` if they are equal.
+    #[inline]
+    pub fn max<R: Clone>(self, rhs: Expr<R>) -> Expr<Select<Less<T, R>, R, T>> {
+        Expr(Select(Less(self.0.clone(), rhs.0.clone()), rhs.0, self.0))
+    }
+}
@@ -0,0 +1,87 @@
+use crate::expr::deriv::Deriv;
+use crate::expr::env::EvalWith;
+use crate::expr::subst::Subst;
+use crate::expr::{Expr, Expression, One, Reduce, Zero};
+
+/// Marker for plain scalar types that can be used directly as constants in operations with
+/// expressions, e.g. `x * 2.0` or `1.0 - x`.
+pub trait Scalar {}
+
+macro_rules! impl_scalar {
+    ($($t:ty)*) => {$(
+        impl Scalar for $t {}
+    )*};
+}
+
+impl_scalar!(f32 f64 i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+/// A constant value in an expression, e.g. a coefficient.
+#[derive(Copy, Clone, Default, Debug, Hash, Ord, Eq, PartialOrd, PartialEq)]
+pub struct Const<T>(pub T);
+
+impl<T> Expression for Const<T> {
+    type Output = T;
+
+    #[inline]
+    fn eval(self) -> Self::Output {
+        self.0
+    }
+}
+
+impl<T: Clone, Env> EvalWith<Env> for Const<T> {
+    type Output = T;
+
+    #[inline]
+    fn eval_with(&self, _: &Env) -> Self::Output {
+        self.0.clone()
+    }
+}
+
+impl<T, Tag, R> Subst<Tag, R> for Const<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn subst(self, _: &R) -> Self::Output {
+        self
+    }
+}
+
+impl<T, Tag> Deriv<Tag> for Const<T> {
+    type Output = Zero<T>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Zero::new()
+    }
+}
+
+impl<T> Reduce for Const<T> {
+    type Output = Self;
+    #[inline(always)]
+    fn reduce(self) -> Self::Output {
+        self
+    }
+}
+
+#[inline]
+pub fn constant<T>(x: T) -> Expr<Const<T>> {
+    Expr(Const(x))
+}
+
+/// The additive identity of the numeric type `T` as an expression. Unlike `constant(0)`, this is
+/// known to be zero at compile-time and is removed by [`Reduce`].
+///
+/// [`Reduce`]: ../trait.Reduce.html
+#[inline]
+pub fn zero<T>() -> Expr<Zero<T>> {
+    Expr(Zero::new())
+}
+
+/// The multiplicative identity of the numeric type `T` as an expression. Unlike `constant(1)`,
+/// this is known to be one at compile-time and is removed by [`Reduce`].
+///
+/// [`Reduce`]: ../trait.Reduce.html
+#[inline]
+pub fn one<T>() -> Expr<One<T>> {
+    Expr(One::new())
+}
@@ -1,6 +1,103 @@
+use crate::expr::Expr;
+use crate::paren::Paren;
+
 pub trait Deriv<Tag> {
     type Output;
     fn deriv(&self) -> Self::Output;
 }
 
 pub type Dv<T, Tag> = <T as Deriv<Tag>>::Output;
+
+impl<T: Deriv<Tag>, Tag> Deriv<Tag> for Expr<T> {
+    type Output = Expr<Dv<T, Tag>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Expr(self.0.deriv())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Derivatives with respect to each tag in a paren of tags.
+pub trait Grad<Tags> {
+    type Output: Paren;
+    fn grad(&self) -> Self::Output;
+}
+
+pub type GradOut<T, Tags> = <T as Grad<Tags>>::Output;
+
+impl<T, Tag, Rest> Grad<(Tag, Rest)> for T
+where
+    T: Deriv<Tag> + Grad<Rest>,
+{
+    type Output = (Dv<T, Tag>, GradOut<T, Rest>);
+
+    #[inline]
+    fn grad(&self) -> Self::Output {
+        (self.deriv(), Grad::<Rest>::grad(self))
+    }
+}
+
+impl<T> Grad<()> for T {
+    type Output = ();
+
+    #[inline(always)]
+    fn grad(&self) -> Self::Output {}
+}
+
+/// Gradients of each expression in a paren of expressions with respect to a paren of tags, with
+/// one row per expression.
+pub trait Jacobian<Tags> {
+    type Output: Paren;
+    fn jacobian(&self) -> Self::Output;
+}
+
+pub type JacobianOut<T, Tags> = <T as Jacobian<Tags>>::Output;
+
+impl<A, B, Tags> Jacobian<Tags> for (A, B)
+where
+    A: Grad<Tags>,
+    B: Jacobian<Tags>,
+{
+    type Output = (GradOut<A, Tags>, JacobianOut<B, Tags>);
+
+    #[inline]
+    fn jacobian(&self) -> Self::Output {
+        (self.0.grad(), self.1.jacobian())
+    }
+}
+
+impl<Tags> Jacobian<Tags> for () {
+    type Output = ();
+
+    #[inline(always)]
+    fn jacobian(&self) -> Self::Output {}
+}
+
+/// Computes the Jacobian of a paren of expressions with respect to a paren of tags.
+/// # Examples
+/// ```
+/// use typing::paren;
+/// use typing::expr::{deriv::jacobian, var::var, Expression};
+/// use typing::tags::{X, Y};
+///
+/// let x = var(2.0, X);
+/// let y = var(3.0, Y);
+///
+/// let j = jacobian(&paren!(x * y, x + y, y), paren!(X, Y));
+/// assert_eq!(j.eval(), paren!(
+///     paren!(3.0, 2.0),
+///     paren!(1.0, 1.0),
+///     paren!(0.0, 1.0),
+/// ));
+/// ```
+#[inline]
+pub fn jacobian<T, Tags>(exprs: &T, _: Tags) -> JacobianOut<T, Tags>
+where
+    T: Jacobian<Tags>,
+{
+    exprs.jacobian()
+}
+
+pub type HessianOut<T, Tags> = JacobianOut<GradOut<T, Tags>, Tags>;
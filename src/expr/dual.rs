@@ -0,0 +1,828 @@
+use crate::boolean::{BoolIfElse, False, IfElse, True};
+use crate::expr::call::{Call, Differentiable};
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{powi, Cos, Exp, IntPow, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use crate::expr::share::Let;
+use crate::expr::unit::Unitless;
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, Expression, One, Zero};
+use crate::func::Func;
+use crate::ident::{IsEq, TypeEq};
+use crate::int::Int;
+use crate::num::{Float, Num};
+use crate::paren::ops::Lookup;
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+
+/// Dual number for forward-mode automatic differentiation, holding a value and its derivative.
+/// The derivative is either a single number of type `T` or a paren of them for several
+/// derivatives at once, see [`Tangent`].
+/// # Examples
+/// ```
+/// use typing::expr::dual::Dual;
+/// use typing::num::Float;
+///
+/// let x = Dual::new(2.0, 1.0);
+/// let y = (x * x).sin();
+///
+/// assert_eq!(y.value, 4.0f64.sin());
+/// assert_eq!(y.deriv, 4.0f64.cos() * 4.0);
+/// ```
+///
+/// [`Tangent`]: trait.Tangent.html
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Dual<T, D = T> {
+    pub value: T,
+    pub deriv: D,
+}
+
+impl<T, D> Dual<T, D> {
+    #[inline]
+    pub fn new(value: T, deriv: D) -> Self {
+        Self { value, deriv }
+    }
+}
+
+impl<T, D: Tangent<T>> Dual<T, D> {
+    /// A value with a derivative of zero.
+    #[inline]
+    pub fn constant(value: T) -> Self {
+        Self::new(value, D::ZERO)
+    }
+}
+
+/// Derivative part of a [`Dual`] number, which is a vector space over `T`.
+///
+/// [`Dual`]: struct.Dual.html
+pub trait Tangent<T>: Copy {
+    const ZERO: Self;
+    fn plus(self, other: Self) -> Self;
+    fn minus(self, other: Self) -> Self;
+    fn scale(self, k: T) -> Self;
+}
+
+impl<T: Float> Tangent<T> for T {
+    const ZERO: Self = T::ZERO;
+
+    #[inline(always)]
+    fn plus(self, other: Self) -> Self {
+        self + other
+    }
+
+    #[inline(always)]
+    fn minus(self, other: Self) -> Self {
+        self - other
+    }
+
+    #[inline(always)]
+    fn scale(self, k: T) -> Self {
+        self * k
+    }
+}
+
+impl<T: Copy, A: Tangent<T>, B: Tangent<T>> Tangent<T> for (A, B) {
+    const ZERO: Self = (A::ZERO, B::ZERO);
+
+    #[inline(always)]
+    fn plus(self, other: Self) -> Self {
+        (self.0.plus(other.0), self.1.plus(other.1))
+    }
+
+    #[inline(always)]
+    fn minus(self, other: Self) -> Self {
+        (self.0.minus(other.0), self.1.minus(other.1))
+    }
+
+    #[inline(always)]
+    fn scale(self, k: T) -> Self {
+        (self.0.scale(k), self.1.scale(k))
+    }
+}
+
+impl<T> Tangent<T> for () {
+    const ZERO: Self = ();
+
+    #[inline(always)]
+    fn plus(self, _: Self) -> Self {}
+
+    #[inline(always)]
+    fn minus(self, _: Self) -> Self {}
+
+    #[inline(always)]
+    fn scale(self, _: T) -> Self {}
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T: Float, D: Tangent<T>> std::ops::Neg for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Dual::new(-self.value, D::ZERO.minus(self.deriv))
+    }
+}
+
+impl<T: Float, D: Tangent<T>> std::ops::Add for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn add(self, rhs: Self) -> Self::Output {
+        Dual::new(self.value + rhs.value, self.deriv.plus(rhs.deriv))
+    }
+}
+
+impl<T: Float, D: Tangent<T>> std::ops::Sub for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn sub(self, rhs: Self) -> Self::Output {
+        Dual::new(self.value - rhs.value, self.deriv.minus(rhs.deriv))
+    }
+}
+
+impl<T: Float, D: Tangent<T>> std::ops::Mul for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn mul(self, rhs: Self) -> Self::Output {
+        Dual::new(
+            self.value * rhs.value,
+            self.deriv
+                .scale(rhs.value)
+                .plus(rhs.deriv.scale(self.value)),
+        )
+    }
+}
+
+impl<T: Float, D: Tangent<T>> std::ops::Div for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn div(self, rhs: Self) -> Self::Output {
+        let value = self.value / rhs.value;
+        Dual::new(
+            value,
+            self.deriv
+                .minus(rhs.deriv.scale(value))
+                .scale(T::ONE / rhs.value),
+        )
+    }
+}
+
+// l % r = l - trunc(l / r) * r
+impl<T, D> std::ops::Rem for Dual<T, D>
+where
+    T: Float + std::ops::Rem<Output = T>,
+    D: Tangent<T>,
+{
+    type Output = Self;
+
+    #[inline]
+    fn rem(self, rhs: Self) -> Self::Output {
+        let value = self.value % rhs.value;
+        let trunc = (self.value - value) / rhs.value;
+        Dual::new(value, self.deriv.minus(rhs.deriv.scale(trunc)))
+    }
+}
+
+impl<T: Float, D: PartialEq> PartialOrd for Dual<T, D> {
+    // duals with the same value but different derivatives are unordered
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.value.partial_cmp(&other.value) {
+            Some(Ordering::Equal) if self.deriv != other.deriv => None,
+            ord => ord,
+        }
+    }
+}
+
+impl<T: Float, D: Tangent<T>> Num for Dual<T, D> {
+    const ZERO: Self = Dual {
+        value: T::ZERO,
+        deriv: D::ZERO,
+    };
+    const ONE: Self = Dual {
+        value: T::ONE,
+        deriv: D::ZERO,
+    };
+}
+
+// so that variables of dual numbers can be raised to type-level powers, e.g. in the quotient rule
+impl<T: Float, D: Tangent<T> + PartialEq, N: Int> IntPow<N> for Dual<T, D> {
+    type Output = Self;
+
+    #[inline]
+    fn int_pow(self) -> Self::Output {
+        self.powi(N::VALUE)
+    }
+}
+
+impl<T, D> Unitless for Dual<T, D> {
+    type Output = Self;
+}
+
+impl<T: Float, D: Tangent<T> + PartialEq> Float for Dual<T, D> {
+    #[inline]
+    fn from_i32(n: i32) -> Self {
+        Dual::constant(T::from_i32(n))
+    }
+
+    #[inline]
+    fn from_i64(n: i64) -> Self {
+        Dual::constant(T::from_i64(n))
+    }
+
+    #[inline]
+    fn sin(self) -> Self {
+        Dual::new(self.value.sin(), self.deriv.scale(self.value.cos()))
+    }
+
+    #[inline]
+    fn cos(self) -> Self {
+        Dual::new(self.value.cos(), self.deriv.scale(-self.value.sin()))
+    }
+
+    #[inline]
+    fn exp(self) -> Self {
+        let value = self.value.exp();
+        Dual::new(value, self.deriv.scale(value))
+    }
+
+    #[inline]
+    fn ln(self) -> Self {
+        Dual::new(self.value.ln(), self.deriv.scale(T::ONE / self.value))
+    }
+
+    #[inline]
+    fn sqrt(self) -> Self {
+        let value = self.value.sqrt();
+        Dual::new(value, self.deriv.scale(T::ONE / (value + value)))
+    }
+
+    #[inline]
+    fn tanh(self) -> Self {
+        let value = self.value.tanh();
+        Dual::new(value, self.deriv.scale(T::ONE - value * value))
+    }
+
+    #[inline]
+    fn powi(self, n: i32) -> Self {
+        let value = self.value.powi(n);
+        // like in powf, zero tangents are skipped since n x^(n - 1) may not be finite, and so is
+        // the exponent zero
+        let mut deriv = D::ZERO;
+        if self.deriv != D::ZERO && n != 0 {
+            let power = match n.checked_sub(1) {
+                Some(m) => self.value.powi(m),
+                None => value / self.value,
+            };
+            deriv = self.deriv.scale(T::from_i32(n) * power);
+        }
+        Dual::new(value, deriv)
+    }
+
+    #[inline]
+    fn powf(self, n: Self) -> Self {
+        let value = self.value.powf(n.value);
+        // zero tangents are skipped, since their factors may not be finite, e.g. ln(x) for x <= 0
+        let mut deriv = D::ZERO;
+        if self.deriv != D::ZERO {
+            let k = n.value * self.value.powf(n.value - T::ONE);
+            deriv = self.deriv.scale(k);
+        }
+        if n.deriv != D::ZERO {
+            deriv = deriv.plus(n.deriv.scale(value * self.value.ln()));
+        }
+        Dual::new(value, deriv)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Seeds of the derivatives of variables of type `T` for [`EvalDual`].
+///
+/// [`EvalDual`]: trait.EvalDual.html
+pub trait Seeds<T> {
+    type Tangent: Tangent<T>;
+}
+
+/// The derivative of the variable tagged with `Tag`.
+pub trait Seed<T, Tag>: Seeds<T> {
+    fn seed(&self) -> Self::Tangent;
+}
+
+/// Seeds for the derivative along a direction, given as a labelled paren of `(tag, component)`
+/// pairs. Variables with tags that aren't listed have a component of zero.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Direction<P>(pub P);
+
+/// Seeds for the derivatives with respect to each tag in a paren of tags, where the derivative of
+/// the variable tagged with the n-th tag is one in the n-th element of the paren.
+pub struct Gradient<Tags>(PhantomData<*const Tags>);
+
+impl<Tags> Gradient<Tags> {
+    #[inline]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Tags> Default for Gradient<Tags> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Tags> Clone for Gradient<Tags> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<Tags> Copy for Gradient<Tags> {}
+
+// inside a Let, the seeds are preceded by the dual number bound to its placeholder
+impl<T, K, X, S: Seeds<T>> Seeds<T> for ((K, X), S) {
+    type Tangent = S::Tangent;
+}
+
+impl<T, Tag, K, X, S: Seed<T, Tag>> Seed<T, Tag> for ((K, X), S) {
+    #[inline(always)]
+    fn seed(&self) -> Self::Tangent {
+        self.1.seed()
+    }
+}
+
+#[doc(hidden)]
+pub trait Component<T, Tag> {
+    fn component(&self) -> T;
+}
+
+#[doc(hidden)]
+pub trait Component2<T, Tag, Marker> {
+    fn component2(&self) -> T;
+}
+
+impl<T: Num, Tag> Component<T, Tag> for () {
+    #[inline(always)]
+    fn component(&self) -> T {
+        T::ZERO
+    }
+}
+
+impl<T, Tag, K, B> Component<T, Tag> for ((K, T), B)
+where
+    K: TypeEq<Tag>,
+    Self: Component2<T, Tag, IsEq<K, Tag>>,
+{
+    #[inline(always)]
+    fn component(&self) -> T {
+        self.component2()
+    }
+}
+
+impl<T: Copy, Tag, K, B> Component2<T, Tag, True> for ((K, T), B) {
+    #[inline(always)]
+    fn component2(&self) -> T {
+        (self.0).1
+    }
+}
+
+impl<T, Tag, K, B: Component<T, Tag>> Component2<T, Tag, False> for ((K, T), B) {
+    #[inline(always)]
+    fn component2(&self) -> T {
+        self.1.component()
+    }
+}
+
+#[doc(hidden)]
+pub trait Zeros<T> {
+    type Output: Tangent<T>;
+}
+
+impl<T: Float, K, B: Zeros<T>> Zeros<T> for (K, B) {
+    type Output = (T, B::Output);
+}
+
+impl<T> Zeros<T> for () {
+    type Output = ();
+}
+
+#[doc(hidden)]
+pub trait OneHot<T, Tag>: Zeros<T> {
+    fn one_hot() -> Self::Output;
+}
+
+impl<T, Tag> OneHot<T, Tag> for () {
+    #[inline(always)]
+    fn one_hot() -> Self::Output {}
+}
+
+impl<T: Float, Tag, K, B> OneHot<T, Tag> for (K, B)
+where
+    K: TypeEq<Tag>,
+    IsEq<K, Tag>: BoolIfElse<One<T>, Zero<T>>,
+    IfElse<IsEq<K, Tag>, One<T>, Zero<T>>: Expression<Output = T> + Default,
+    B: OneHot<T, Tag>,
+{
+    #[inline(always)]
+    fn one_hot() -> Self::Output {
+        (
+            IfElse::<IsEq<K, Tag>, One<T>, Zero<T>>::default().eval(),
+            B::one_hot(),
+        )
+    }
+}
+
+impl<T: Float, P> Seeds<T> for Direction<P> {
+    type Tangent = T;
+}
+
+impl<T: Float, Tag, P: Component<T, Tag>> Seed<T, Tag> for Direction<P> {
+    #[inline(always)]
+    fn seed(&self) -> Self::Tangent {
+        self.0.component()
+    }
+}
+
+impl<T, Tags: Zeros<T>> Seeds<T> for Gradient<Tags> {
+    type Tangent = Tags::Output;
+}
+
+impl<T, Tag, Tags: OneHot<T, Tag>> Seed<T, Tag> for Gradient<Tags> {
+    #[inline(always)]
+    fn seed(&self) -> Self::Tangent {
+        Tags::one_hot()
+    }
+}
+
+/// Seeds preceded by the dual numbers of the placeholders in a labelled paren of `(tag, value)`
+/// pairs, where the derivative of each placeholder is seeded like that of a variable.
+#[doc(hidden)]
+pub trait DualEnv<S> {
+    type Output;
+    fn dual_env(&self, seeds: S) -> Self::Output;
+}
+
+pub type DualEnvOut<Env, S> = <Env as DualEnv<S>>::Output;
+
+impl<S> DualEnv<S> for () {
+    type Output = S;
+
+    #[inline(always)]
+    fn dual_env(&self, seeds: S) -> Self::Output {
+        seeds
+    }
+}
+
+impl<T: Clone, K: Clone, B: DualEnv<S>, S: Seed<T, K>> DualEnv<S> for ((K, T), B) {
+    type Output = ((K, Dual<T, S::Tangent>), B::Output);
+
+    #[inline]
+    fn dual_env(&self, seeds: S) -> Self::Output {
+        let (tag, value) = &self.0;
+        let dual = Dual::new(value.clone(), seeds.seed());
+        ((tag.clone(), dual), self.1.dual_env(seeds))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Evaluation of an expression with [`Dual`] numbers, giving its value and derivatives in a single
+/// pass without building the symbolic derivative.
+///
+/// [`Dual`]: struct.Dual.html
+pub trait EvalDual<S> {
+    type Output;
+    fn eval_dual(&self, seeds: &S) -> Self::Output;
+}
+
+pub type EvalDualOut<T, S> = <T as EvalDual<S>>::Output;
+
+impl<T: EvalDual<S>, S> EvalDual<S> for Expr<T> {
+    type Output = T::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        self.0.eval_dual(seeds)
+    }
+}
+
+impl<A: EvalDual<S>, B: EvalDual<S>, S> EvalDual<S> for (A, B) {
+    type Output = (A::Output, B::Output);
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        (self.0.eval_dual(seeds), self.1.eval_dual(seeds))
+    }
+}
+
+impl<S> EvalDual<S> for () {
+    type Output = ();
+
+    #[inline(always)]
+    fn eval_dual(&self, _: &S) -> Self::Output {}
+}
+
+impl<T: Clone, Tag, D, S: Seed<T, Tag>> EvalDual<S> for Var<T, Tag, D> {
+    type Output = Dual<T, S::Tangent>;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        Dual::new(self.clone().eval(), seeds.seed())
+    }
+}
+
+impl<T, Tag, D, S> EvalDual<S> for Placeholder<T, Tag, D>
+where
+    S: Lookup<Tag>,
+    S::Output: Clone,
+{
+    type Output = S::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        seeds.lookup().clone()
+    }
+}
+
+impl<T: Clone, S: Seeds<T>> EvalDual<S> for Const<T> {
+    type Output = Dual<T, S::Tangent>;
+
+    #[inline]
+    fn eval_dual(&self, _: &S) -> Self::Output {
+        Dual::constant(self.0.clone())
+    }
+}
+
+macro_rules! eval_dual_identity {
+    ($($name:ident => $value:ident),*) => {$(
+        impl<T: Num, S: Seeds<T>> EvalDual<S> for $name<T> {
+            type Output = Dual<T, S::Tangent>;
+
+            #[inline]
+            fn eval_dual(&self, _: &S) -> Self::Output {
+                Dual::constant(T::$value)
+            }
+        }
+    )*};
+}
+
+eval_dual_identity!(Zero => ZERO, One => ONE);
+
+impl<T: EvalDual<S>, S> EvalDual<S> for Neg<T>
+where
+    T::Output: std::ops::Neg,
+{
+    type Output = <T::Output as std::ops::Neg>::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        -self.0.eval_dual(seeds)
+    }
+}
+
+macro_rules! eval_dual_binary {
+    ($($name:ident, $method:ident);*) => {$(
+        impl<L, R, S> EvalDual<S> for $name<L, R>
+        where
+            L: EvalDual<S>,
+            R: EvalDual<S>,
+            L::Output: std::ops::$name<R::Output>,
+        {
+            type Output = <L::Output as std::ops::$name<R::Output>>::Output;
+
+            #[inline]
+            fn eval_dual(&self, seeds: &S) -> Self::Output {
+                std::ops::$name::$method(self.0.eval_dual(seeds), self.1.eval_dual(seeds))
+            }
+        }
+    )*};
+}
+
+eval_dual_binary!(Add, add; Sub, sub; Mul, mul; Div, div; Rem, rem);
+
+macro_rules! eval_dual_elementary {
+    ($($name:ident, $method:ident);*) => {$(
+        impl<T: EvalDual<S>, S> EvalDual<S> for $name<T>
+        where
+            T::Output: Float,
+        {
+            type Output = T::Output;
+
+            #[inline]
+            fn eval_dual(&self, seeds: &S) -> Self::Output {
+                Float::$method(self.0.eval_dual(seeds))
+            }
+        }
+    )*};
+}
+
+eval_dual_elementary!(Sin, sin; Cos, cos; Exp, exp; Ln, ln; Sqrt, sqrt; Tanh, tanh);
+
+impl<T: EvalDual<S>, S> EvalDual<S> for Powi<T>
+where
+    T::Output: Float,
+{
+    type Output = T::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        powi(self.0.eval_dual(seeds), self.1)
+    }
+}
+
+impl<T: EvalDual<S>, N: Int, S> EvalDual<S> for Pow<T, N>
+where
+    T::Output: Float,
+{
+    type Output = T::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        self.0.eval_dual(seeds).powi(N::VALUE)
+    }
+}
+
+impl<L, R, S> EvalDual<S> for Powf<L, R>
+where
+    L: EvalDual<S>,
+    R: EvalDual<S, Output = L::Output>,
+    L::Output: Float,
+{
+    type Output = L::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        self.0.eval_dual(seeds).powf(self.1.eval_dual(seeds))
+    }
+}
+
+// the derivative is scaled by the derivative function at the value
+impl<F, T, S, V, D> EvalDual<S> for Call<F, T>
+where
+    F: Differentiable + Func<V, Output = V>,
+    F::Deriv: Func<V, Output = V>,
+    T: EvalDual<S, Output = Dual<V, D>>,
+    V: Copy,
+    D: Tangent<V>,
+{
+    type Output = Dual<V, D>;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        let x = self.1.eval_dual(seeds);
+        Dual::new(F::call(x.value), x.deriv.scale(F::Deriv::call(x.value)))
+    }
+}
+
+// comparisons only look at the values, unlike the partial order of the duals themselves
+macro_rules! eval_dual_compare {
+    ($($name:ident, $trait:ident::$op:ident;)*) => {$(
+        impl<L, R, S, V, D, E> EvalDual<S> for $name<L, R>
+        where
+            L: EvalDual<S, Output = Dual<V, D>>,
+            R: EvalDual<S, Output = Dual<V, E>>,
+            V: $trait,
+        {
+            type Output = bool;
+
+            #[inline]
+            fn eval_dual(&self, seeds: &S) -> Self::Output {
+                self.0.eval_dual(seeds).value.$op(&self.1.eval_dual(seeds).value)
+            }
+        }
+    )*};
+}
+
+eval_dual_compare!(
+    Less, PartialOrd::lt;
+    LessEq, PartialOrd::le;
+    Greater, PartialOrd::gt;
+    GreaterEq, PartialOrd::ge;
+    Equal, PartialEq::eq;
+    NotEqual, PartialEq::ne;
+);
+
+impl<C, A, B, S> EvalDual<S> for Select<C, A, B>
+where
+    C: EvalDual<S>,
+    C::Output: Into<bool>,
+    A: EvalDual<S>,
+    B: EvalDual<S, Output = A::Output>,
+{
+    type Output = A::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        if self.0.eval_dual(seeds).into() {
+            self.1.eval_dual(seeds)
+        } else {
+            self.2.eval_dual(seeds)
+        }
+    }
+}
+
+// the bound value is evaluated once and looked up by the placeholders in the body
+impl<Tag, V, B, S> EvalDual<S> for Let<Tag, V, B>
+where
+    Tag: Clone,
+    V: EvalDual<S>,
+    B: EvalDual<((Tag, V::Output), S)>,
+    S: Clone,
+{
+    type Output = B::Output;
+
+    #[inline]
+    fn eval_dual(&self, seeds: &S) -> Self::Output {
+        let value = self.1.eval_dual(seeds);
+        self.2.eval_dual(&((self.0.clone(), value), seeds.clone()))
+    }
+}
+
+impl<T> Expr<T> {
+    /// Evaluate the expression together with its derivative along a direction, given as a
+    /// labelled paren of `(tag, component)` pairs where missing tags have a component of zero.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::var;
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    /// let f = x * x * y;
+    ///
+    /// let d = f.eval_dual(paren!((X, 1.0)));
+    /// assert_eq!((d.value, d.deriv), (12.0, 12.0));
+    /// assert_eq!(f.eval_dual(paren!((X, 1.0), (Y, -2.0))).deriv, 4.0);
+    /// ```
+    #[inline]
+    pub fn eval_dual<P>(&self, direction: P) -> EvalDualOut<T, Direction<P>>
+    where
+        T: EvalDual<Direction<P>>,
+    {
+        self.0.eval_dual(&Direction(direction))
+    }
+
+    /// Like [`eval_dual`], but with the values of the placeholders taken from an environment,
+    /// given as a labelled paren of `(tag, value)` pairs like for [`eval_with`]. The placeholders
+    /// have the components of their tags in the direction, like variables.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::{placeholder, var};
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = placeholder::<f64, _>(X);
+    /// let y = var(3.0, Y);
+    /// let f = x * x * y;
+    ///
+    /// let d = f.eval_dual_with(&paren!((X, 2.0)), paren!((X, 1.0)));
+    /// assert_eq!((d.value, d.deriv), (12.0, 12.0));
+    /// assert_eq!(f.eval_dual_with(&paren!((X, 2.0)), paren!((Y, 1.0))).deriv, 4.0);
+    /// ```
+    ///
+    /// [`eval_dual`]: #method.eval_dual
+    /// [`eval_with`]: #method.eval_with
+    #[inline]
+    pub fn eval_dual_with<Env, P>(
+        &self,
+        env: &Env,
+        direction: P,
+    ) -> EvalDualOut<T, DualEnvOut<Env, Direction<P>>>
+    where
+        Env: DualEnv<Direction<P>>,
+        T: EvalDual<DualEnvOut<Env, Direction<P>>>,
+    {
+        self.0.eval_dual(&env.dual_env(Direction(direction)))
+    }
+
+    /// Evaluate the expression together with its derivatives with respect to each tag in a paren
+    /// of tags, using dual numbers holding a paren of derivatives.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::var;
+    /// use typing::tags::{X, Y, Z};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    /// let f = (x * y).ln() + x / y;
+    ///
+    /// let d = f.eval_dual_grad(paren!(X, Y, Z));
+    /// assert_eq!(d.deriv, paren!(0.5 + 1.0 / 3.0, 1.0 / 3.0 - 2.0 / 9.0, 0.0));
+    /// ```
+    #[inline]
+    pub fn eval_dual_grad<Tags>(&self, _: Tags) -> EvalDualOut<T, Gradient<Tags>>
+    where
+        T: EvalDual<Gradient<Tags>>,
+    {
+        self.0.eval_dual(&Gradient::new())
+    }
+}
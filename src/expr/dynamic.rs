@@ -0,0 +1,1075 @@
+use crate::expr::call::{self, DerivFn, Differentiable};
+use crate::expr::print::{print_call, print_let, render, Notation, Print, ATOM};
+use crate::expr::{cond, constant, elementary, ops, share, var, Expr, One, Zero};
+use crate::func::Func;
+use crate::int::Int;
+use crate::num::{Float, Num};
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+use std::hash::Hash;
+use std::str::FromStr;
+use DynExpr::*;
+
+/// An expression tree built at runtime, e.g. by parsing a formula, with the same arithmetic,
+/// elementary and conditional nodes as the static expressions. Variables are referred to by name.
+/// Comparisons evaluate to one if they hold and to zero otherwise, and `Select` chooses its second
+/// operand if the condition is nonzero. User-defined functions are [`DynFunc`]s. `Let` binds the
+/// value of an expression to a name in its body, shadowing any variable of that name.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use typing::expr::dynamic::DynExpr;
+///
+/// let f: DynExpr = "(x + y*x)/z".parse().unwrap();
+/// let vars: HashMap<_, _> = vec![("x", 8.0), ("y", 2.0), ("z", -1.0)].into_iter().collect();
+///
+/// assert_eq!(f.eval(&vars), Ok(-24.0));
+/// assert_eq!(f.deriv("y").unwrap().eval(&vars), Ok(-8.0));
+/// assert_eq!(f.to_string(), "(x + y * x) / z");
+/// ```
+///
+/// [`DynFunc`]: struct.DynFunc.html
+#[derive(Clone, Debug, PartialEq)]
+pub enum DynExpr<T = f64> {
+    Const(T),
+    Var(String),
+    Neg(Box<DynExpr<T>>),
+    Add(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Sub(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Mul(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Div(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Rem(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Sin(Box<DynExpr<T>>),
+    Cos(Box<DynExpr<T>>),
+    Exp(Box<DynExpr<T>>),
+    Ln(Box<DynExpr<T>>),
+    Sqrt(Box<DynExpr<T>>),
+    Tanh(Box<DynExpr<T>>),
+    Powi(Box<DynExpr<T>>, i64),
+    Powf(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Less(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    LessEq(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Greater(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    GreaterEq(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Equal(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    NotEqual(Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Select(Box<DynExpr<T>>, Box<DynExpr<T>>, Box<DynExpr<T>>),
+    Call(Box<DynFunc<T>>, Box<DynExpr<T>>),
+    Let(Box<(String, DynExpr<T>)>, Box<DynExpr<T>>),
+}
+
+/// User-defined function in a [`DynExpr`], named by the `Debug` output of its functor. The
+/// derivatives of a [`Differentiable`] function are known along its chain of derivatives, which
+/// ends in a function implementing [`DynDifferentiable`] without being differentiable itself.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use typing::expr::call::Differentiable;
+/// use typing::expr::dynamic::{DynDifferentiable, DynExpr, DynFunc, UnknownDeriv};
+/// use typing::impl_func;
+///
+/// #[derive(Copy, Clone, Debug, Default)]
+/// struct Cube;
+/// #[derive(Copy, Clone, Debug, Default)]
+/// struct Square3;
+/// impl_func!(for Cube { |x: f64| -> f64 { x * x * x } });
+/// impl_func!(for Square3 { |x: f64| -> f64 { 3.0 * x * x } });
+/// impl Differentiable for Cube {
+///     type Deriv = Square3;
+/// }
+/// impl DynDifferentiable<f64> for Square3 {}
+///
+/// let x = Box::new(DynExpr::Var("x".to_string()));
+/// let f = DynExpr::Call(Box::new(DynFunc::differentiable::<Cube>()), x);
+/// let vars: HashMap<_, _> = vec![("x", 2.0)].into_iter().collect();
+/// assert_eq!(f.to_string(), "Cube(x)");
+/// assert_eq!(f.eval(&vars), Ok(8.0));
+/// let df = f.deriv("x").unwrap();
+/// assert_eq!(df.eval(&vars), Ok(12.0));
+/// assert_eq!(df.deriv("x"), Err(UnknownDeriv("Square3".to_string())));
+/// ```
+///
+/// [`DynExpr`]: enum.DynExpr.html
+/// [`Differentiable`]: ../call/trait.Differentiable.html
+/// [`DynDifferentiable`]: trait.DynDifferentiable.html
+#[derive(Clone, Debug)]
+pub struct DynFunc<T> {
+    pub name: String,
+    pub call: fn(T) -> T,
+    pub deriv: Option<fn() -> DynFunc<T>>,
+}
+
+impl<T> DynFunc<T> {
+    /// A function without a known derivative.
+    pub fn new<F: Func<T, Output = T> + Default + Debug>() -> Self {
+        DynFunc {
+            name: format!("{:?}", F::default()),
+            call: F::call,
+            deriv: None,
+        }
+    }
+
+    /// A function whose derivative is its [`Differentiable::Deriv`], with the derivatives of that
+    /// as far as they're known.
+    ///
+    /// [`Differentiable::Deriv`]: ../call/trait.Differentiable.html#associatedtype.Deriv
+    pub fn differentiable<F>() -> Self
+    where
+        F: Differentiable + Func<T, Output = T> + Default + Debug,
+        DerivFn<F>: DynDifferentiable<T>,
+    {
+        DynFunc {
+            deriv: Some(DerivFn::<F>::dyn_func),
+            ..Self::new::<F>()
+        }
+    }
+}
+
+/// Functors that can be turned into a [`DynFunc`] together with their chain of derivatives. It's
+/// implemented for every [`Differentiable`] functor whose derivative implements it again, and
+/// functions without a known derivative implement it with the default method to end the chain.
+/// A function that is its own derivative, or otherwise ends in a cycle, can't be bridged this
+/// way, but its `DynFunc` can be built by a function that refers to itself as the derivative.
+///
+/// [`DynFunc`]: struct.DynFunc.html
+/// [`Differentiable`]: ../call/trait.Differentiable.html
+pub trait DynDifferentiable<T>: Func<T, Output = T> + Default + Debug {
+    fn dyn_func() -> DynFunc<T> {
+        DynFunc::new::<Self>()
+    }
+}
+
+impl<T, F> DynDifferentiable<T> for F
+where
+    F: Differentiable + Func<T, Output = T> + Default + Debug,
+    DerivFn<F>: DynDifferentiable<T>,
+{
+    fn dyn_func() -> DynFunc<T> {
+        DynFunc::differentiable::<F>()
+    }
+}
+
+// functions are compared by name, as the addresses of function pointers aren't unique
+impl<T> PartialEq for DynFunc<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+/// Error when evaluating a [`DynExpr`](enum.DynExpr.html) with a variable that isn't bound.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnboundVariable(pub String);
+
+impl Display for UnboundVariable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "unbound variable `{}`", self.0)
+    }
+}
+
+impl Error for UnboundVariable {}
+
+/// Error when differentiating a [`DynExpr`](enum.DynExpr.html) that calls a function whose
+/// derivative isn't known.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnknownDeriv(pub String);
+
+impl Display for UnknownDeriv {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "the derivative of `{}` isn't known", self.0)
+    }
+}
+
+impl Error for UnknownDeriv {}
+
+/// Error when parsing a [`DynExpr`](enum.DynExpr.html), with the byte offset in the input at
+/// which it occurred.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} at position {}", self.message, self.position)
+    }
+}
+
+impl Error for ParseError {}
+
+impl<T: Float + std::ops::Rem<Output = T>> DynExpr<T> {
+    /// Evaluate the expression with the values of the variables in `vars`.
+    pub fn eval<K>(&self, vars: &HashMap<K, T>) -> Result<T, UnboundVariable>
+    where
+        K: Borrow<str> + Hash + Eq,
+    {
+        self.eval_in(&|name| vars.get(name).copied())
+    }
+
+    // bindings extend the lookup of the variables in their body
+    fn eval_in(&self, vars: &dyn Fn(&str) -> Option<T>) -> Result<T, UnboundVariable> {
+        Ok(match self {
+            Const(c) => *c,
+            Var(name) => match vars(name) {
+                Some(value) => value,
+                None => return Err(UnboundVariable(name.clone())),
+            },
+            Neg(x) => -x.eval_in(vars)?,
+            Add(l, r) => l.eval_in(vars)? + r.eval_in(vars)?,
+            Sub(l, r) => l.eval_in(vars)? - r.eval_in(vars)?,
+            Mul(l, r) => l.eval_in(vars)? * r.eval_in(vars)?,
+            Div(l, r) => l.eval_in(vars)? / r.eval_in(vars)?,
+            Rem(l, r) => l.eval_in(vars)? % r.eval_in(vars)?,
+            Sin(x) => x.eval_in(vars)?.sin(),
+            Cos(x) => x.eval_in(vars)?.cos(),
+            Exp(x) => x.eval_in(vars)?.exp(),
+            Ln(x) => x.eval_in(vars)?.ln(),
+            Sqrt(x) => x.eval_in(vars)?.sqrt(),
+            Tanh(x) => x.eval_in(vars)?.tanh(),
+            Powi(x, n) => elementary::powi(x.eval_in(vars)?, *n),
+            Powf(l, r) => l.eval_in(vars)?.powf(r.eval_in(vars)?),
+            Less(l, r) => truth(l.eval_in(vars)? < r.eval_in(vars)?),
+            LessEq(l, r) => truth(l.eval_in(vars)? <= r.eval_in(vars)?),
+            Greater(l, r) => truth(l.eval_in(vars)? > r.eval_in(vars)?),
+            GreaterEq(l, r) => truth(l.eval_in(vars)? >= r.eval_in(vars)?),
+            Equal(l, r) => truth(l.eval_in(vars)? == r.eval_in(vars)?),
+            NotEqual(l, r) => truth(l.eval_in(vars)? != r.eval_in(vars)?),
+            Call(func, x) => (func.call)(x.eval_in(vars)?),
+            Select(c, a, b) => {
+                if c.eval_in(vars)? != T::ZERO {
+                    a.eval_in(vars)?
+                } else {
+                    b.eval_in(vars)?
+                }
+            }
+            Let(binding, body) => {
+                let (name, value) = &**binding;
+                let value = value.eval_in(vars)?;
+                body.eval_in(&|n| if n == name { Some(value) } else { vars(n) })?
+            }
+        })
+    }
+}
+
+fn truth<T: Num>(b: bool) -> T {
+    if b {
+        T::ONE
+    } else {
+        T::ZERO
+    }
+}
+
+impl<T: Float> DynExpr<T> {
+    /// Symbolic derivative with respect to the variable `var`, following the same rules as the
+    /// static expressions and removing terms that are zero. Fails if the expression calls a
+    /// [`DynFunc`](struct.DynFunc.html) without a known derivative on an argument that depends on
+    /// `var`.
+    pub fn deriv(&self, var: &str) -> Result<Self, UnknownDeriv> {
+        // the derivatives of the operands are combined out of line, which keeps the frames of the
+        // recursion small
+        Ok(match self {
+            Const(_) => Const(T::ZERO),
+            Var(name) => Const(if name == var { T::ONE } else { T::ZERO }),
+            // comparisons are piecewise constant
+            Less(..) | LessEq(..) | Greater(..) | GreaterEq(..) | Equal(..) | NotEqual(..) => {
+                Const(T::ZERO)
+            }
+            Neg(x)
+            | Sin(x)
+            | Cos(x)
+            | Exp(x)
+            | Ln(x)
+            | Sqrt(x)
+            | Tanh(x)
+            | Powi(x, _)
+            | Call(_, x) => {
+                let dx = x.deriv(var)?;
+                self.chain(dx)?
+            }
+            Add(l, r) | Sub(l, r) | Mul(l, r) | Div(l, r) | Rem(l, r) | Powf(l, r) => {
+                let dl = l.deriv(var)?;
+                let dr = r.deriv(var)?;
+                self.chain2(dl, dr)
+            }
+            Select(c, a, b) => {
+                let da = a.deriv(var)?;
+                let db = b.deriv(var)?;
+                select(c, da, db)
+            }
+            // the variable is shadowed in the body if it's named like the binding
+            Let(binding, body) => {
+                let (name, value) = &**binding;
+                let direct = if name == var {
+                    Const(T::ZERO)
+                } else {
+                    body.deriv(var)?
+                };
+                let dv = value.deriv(var)?;
+                let dt = if is(&dv, T::ZERO) {
+                    Const(T::ZERO)
+                } else {
+                    body.deriv(name)?
+                };
+                bind_deriv(name, value, direct, dv, dt)
+            }
+        })
+    }
+
+    // derivative of a node with a single operand, whose derivative is dx
+    fn chain(&self, dx: Self) -> Result<Self, UnknownDeriv> {
+        Ok(match self {
+            Neg(_) => neg(dx),
+            Sin(x) => mul(Cos(x.clone()), dx),
+            Cos(x) => neg(mul(Sin(x.clone()), dx)),
+            Exp(_) => mul(self.clone(), dx),
+            Ln(x) => div(dx, (**x).clone()),
+            Sqrt(_) => div(dx, add(self.clone(), self.clone())),
+            Tanh(_) => sub(dx.clone(), mul(mul(self.clone(), self.clone()), dx)),
+            Powi(x, n) => {
+                // x^(n - 1) = x^n / x if n - 1 isn't representable
+                let power = match n.checked_sub(1) {
+                    Some(m) => Powi(x.clone(), m),
+                    None => div(self.clone(), (**x).clone()),
+                };
+                mul(mul(Const(T::from_i64(*n)), power), dx)
+            }
+            Call(_, _) if is(&dx, T::ZERO) => dx,
+            Call(func, x) => match func.deriv {
+                Some(deriv) => mul(Call(Box::new(deriv()), x.clone()), dx),
+                None => return Err(UnknownDeriv(func.name.clone())),
+            },
+            _ => unreachable!(),
+        })
+    }
+
+    // derivative of a node with two operands, whose derivatives are dl and dr
+    fn chain2(&self, dl: Self, dr: Self) -> Self {
+        match self {
+            Add(..) => add(dl, dr),
+            Sub(..) => sub(dl, dr),
+            Mul(l, r) => add(mul(dl, (**r).clone()), mul((**l).clone(), dr)),
+            Div(l, r) => sub(
+                div(dl, (**r).clone()),
+                mul(div((**l).clone(), mul((**r).clone(), (**r).clone())), dr),
+            ),
+            // l % r = l - trunc(l / r) * r, where trunc(l / r) = (l - l % r) / r
+            Rem(l, r) => sub(
+                dl,
+                mul(div(sub((**l).clone(), self.clone()), (**r).clone()), dr),
+            ),
+            Powf(l, r) => mul(
+                self.clone(),
+                add(
+                    mul(dr, Ln(l.clone())),
+                    div(mul((**r).clone(), dl), (**l).clone()),
+                ),
+            ),
+            _ => unreachable!(),
+        }
+    }
+}
+
+fn select<T: Float>(c: &DynExpr<T>, da: DynExpr<T>, db: DynExpr<T>) -> DynExpr<T> {
+    if is(&da, T::ZERO) && is(&db, T::ZERO) {
+        Const(T::ZERO)
+    } else {
+        Select(Box::new(c.clone()), Box::new(da), Box::new(db))
+    }
+}
+
+// d/dx (let t = v in b) = let t = v in (db/dx + db/dt dv/dx), where dv/dx is taken outside the
+// binding if it refers to a variable named t
+fn bind_deriv<T: Float>(
+    name: &str,
+    value: &DynExpr<T>,
+    direct: DynExpr<T>,
+    dv: DynExpr<T>,
+    dt: DynExpr<T>,
+) -> DynExpr<T> {
+    if is(&dv, T::ZERO) {
+        bind(name, value, direct)
+    } else if dv.uses(name) {
+        add(bind(name, value, direct), mul(bind(name, value, dt), dv))
+    } else {
+        bind(name, value, add(direct, mul(dt, dv)))
+    }
+}
+
+impl<T> DynExpr<T> {
+    // whether a variable named `name` occurs in the expression outside of bindings shadowing it
+    fn uses(&self, name: &str) -> bool {
+        match self {
+            Const(_) => false,
+            Var(n) => n == name,
+            Neg(x)
+            | Sin(x)
+            | Cos(x)
+            | Exp(x)
+            | Ln(x)
+            | Sqrt(x)
+            | Tanh(x)
+            | Powi(x, _)
+            | Call(_, x) => x.uses(name),
+            Add(l, r)
+            | Sub(l, r)
+            | Mul(l, r)
+            | Div(l, r)
+            | Rem(l, r)
+            | Powf(l, r)
+            | Less(l, r)
+            | LessEq(l, r)
+            | Greater(l, r)
+            | GreaterEq(l, r)
+            | Equal(l, r)
+            | NotEqual(l, r) => l.uses(name) || r.uses(name),
+            Select(c, a, b) => c.uses(name) || a.uses(name) || b.uses(name),
+            Let(binding, body) => binding.1.uses(name) || (binding.0 != name && body.uses(name)),
+        }
+    }
+
+    /// Print the expression as LaTeX math.
+    pub fn to_latex(&self) -> String
+    where
+        T: Display,
+    {
+        render(self, Notation::Latex)
+    }
+
+    /// Print the expression as an S-expression.
+    pub fn to_sexpr(&self) -> String
+    where
+        T: Display,
+    {
+        render(self, Notation::SExpr)
+    }
+}
+
+// constructors removing the terms that are zero, like Reduce
+fn is<T: Float>(e: &DynExpr<T>, value: T) -> bool {
+    match e {
+        Const(c) => *c == value,
+        _ => false,
+    }
+}
+
+fn neg<T: Float>(x: DynExpr<T>) -> DynExpr<T> {
+    match x {
+        Const(c) => Const(-c),
+        Neg(x) => *x,
+        x => Neg(Box::new(x)),
+    }
+}
+
+fn add<T: Float>(l: DynExpr<T>, r: DynExpr<T>) -> DynExpr<T> {
+    if is(&l, T::ZERO) {
+        r
+    } else if is(&r, T::ZERO) {
+        l
+    } else {
+        Add(Box::new(l), Box::new(r))
+    }
+}
+
+fn sub<T: Float>(l: DynExpr<T>, r: DynExpr<T>) -> DynExpr<T> {
+    if is(&r, T::ZERO) {
+        l
+    } else if is(&l, T::ZERO) {
+        neg(r)
+    } else {
+        Sub(Box::new(l), Box::new(r))
+    }
+}
+
+fn mul<T: Float>(l: DynExpr<T>, r: DynExpr<T>) -> DynExpr<T> {
+    if is(&l, T::ZERO) || is(&r, T::ZERO) {
+        Const(T::ZERO)
+    } else if is(&l, T::ONE) {
+        r
+    } else if is(&r, T::ONE) {
+        l
+    } else {
+        Mul(Box::new(l), Box::new(r))
+    }
+}
+
+// bindings are dropped if they aren't used
+fn bind<T: Clone>(name: &str, value: &DynExpr<T>, body: DynExpr<T>) -> DynExpr<T> {
+    if body.uses(name) {
+        Let(Box::new((name.to_string(), value.clone())), Box::new(body))
+    } else {
+        body
+    }
+}
+
+fn div<T: Float>(l: DynExpr<T>, r: DynExpr<T>) -> DynExpr<T> {
+    if is(&l, T::ZERO) {
+        Const(T::ZERO)
+    } else if is(&r, T::ONE) {
+        l
+    } else {
+        Div(Box::new(l), Box::new(r))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// printing reuses the static nodes on references to the children
+impl<T: Display> Print for DynExpr<T> {
+    fn precedence(&self, notation: Notation) -> u8 {
+        match self {
+            Const(c) => constant::Const(c).precedence(notation),
+            Var(_) => ATOM,
+            Neg(x) => ops::Neg(&**x).precedence(notation),
+            Add(l, r) => ops::Add(&**l, &**r).precedence(notation),
+            Sub(l, r) => ops::Sub(&**l, &**r).precedence(notation),
+            Mul(l, r) => ops::Mul(&**l, &**r).precedence(notation),
+            Div(l, r) => ops::Div(&**l, &**r).precedence(notation),
+            Rem(l, r) => ops::Rem(&**l, &**r).precedence(notation),
+            Sin(x) => elementary::Sin(&**x).precedence(notation),
+            Cos(x) => elementary::Cos(&**x).precedence(notation),
+            Exp(x) => elementary::Exp(&**x).precedence(notation),
+            Ln(x) => elementary::Ln(&**x).precedence(notation),
+            Sqrt(x) => elementary::Sqrt(&**x).precedence(notation),
+            Tanh(x) => elementary::Tanh(&**x).precedence(notation),
+            Powi(x, n) => elementary::Powi(&**x, *n).precedence(notation),
+            Powf(l, r) => elementary::Powf(&**l, &**r).precedence(notation),
+            Less(l, r) => cond::Less(&**l, &**r).precedence(notation),
+            LessEq(l, r) => cond::LessEq(&**l, &**r).precedence(notation),
+            Greater(l, r) => cond::Greater(&**l, &**r).precedence(notation),
+            GreaterEq(l, r) => cond::GreaterEq(&**l, &**r).precedence(notation),
+            Equal(l, r) => cond::Equal(&**l, &**r).precedence(notation),
+            NotEqual(l, r) => cond::NotEqual(&**l, &**r).precedence(notation),
+            Select(c, a, b) => cond::Select(&**c, &**a, &**b).precedence(notation),
+            Call(..) | Let(..) => ATOM,
+        }
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        match self {
+            Const(c) => constant::Const(c).print(notation, f),
+            Var(name) => f.write_str(name),
+            Neg(x) => ops::Neg(&**x).print(notation, f),
+            Add(l, r) => ops::Add(&**l, &**r).print(notation, f),
+            Sub(l, r) => ops::Sub(&**l, &**r).print(notation, f),
+            Mul(l, r) => ops::Mul(&**l, &**r).print(notation, f),
+            Div(l, r) => ops::Div(&**l, &**r).print(notation, f),
+            Rem(l, r) => ops::Rem(&**l, &**r).print(notation, f),
+            Sin(x) => elementary::Sin(&**x).print(notation, f),
+            Cos(x) => elementary::Cos(&**x).print(notation, f),
+            Exp(x) => elementary::Exp(&**x).print(notation, f),
+            Ln(x) => elementary::Ln(&**x).print(notation, f),
+            Sqrt(x) => elementary::Sqrt(&**x).print(notation, f),
+            Tanh(x) => elementary::Tanh(&**x).print(notation, f),
+            Powi(x, n) => elementary::Powi(&**x, *n).print(notation, f),
+            Powf(l, r) => elementary::Powf(&**l, &**r).print(notation, f),
+            Less(l, r) => cond::Less(&**l, &**r).print(notation, f),
+            LessEq(l, r) => cond::LessEq(&**l, &**r).print(notation, f),
+            Greater(l, r) => cond::Greater(&**l, &**r).print(notation, f),
+            GreaterEq(l, r) => cond::GreaterEq(&**l, &**r).print(notation, f),
+            Equal(l, r) => cond::Equal(&**l, &**r).print(notation, f),
+            NotEqual(l, r) => cond::NotEqual(&**l, &**r).print(notation, f),
+            Select(c, a, b) => cond::Select(&**c, &**a, &**b).print(notation, f),
+            Call(func, x) => print_call(&func.name, &**x, notation, f),
+            Let(binding, body) => print_let(&binding.0, &binding.1, &**body, notation, f),
+        }
+    }
+}
+
+impl<T: Display> Display for DynExpr<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.print(Notation::Infix, f)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Conversion of static expressions into a [`DynExpr`](enum.DynExpr.html), where variables are
+/// named by the `Debug` output of their tags. The values stored in variables aren't kept, so they
+/// have to be bound again when evaluating.
+/// # Examples
+/// ```
+/// use std::collections::HashMap;
+/// use typing::expr::{dynamic::ToDyn, var::var};
+/// use typing::tags::{X, Y};
+///
+/// let x = var(2.0, X);
+/// let y = var(3.0, Y);
+/// let f = (x * y).sin().to_dyn();
+///
+/// assert_eq!(f.to_string(), "sin(X * Y)");
+/// let vars: HashMap<_, _> = vec![("X", 2.0), ("Y", 3.0)].into_iter().collect();
+/// assert_eq!(f.deriv("X").unwrap().eval(&vars), Ok(6.0f64.cos() * 3.0));
+/// ```
+pub trait ToDyn<T> {
+    fn to_dyn(&self) -> DynExpr<T>;
+}
+
+impl<T, E: ToDyn<T>> ToDyn<T> for Expr<E> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        self.0.to_dyn()
+    }
+}
+
+impl<T: Clone> ToDyn<T> for constant::Const<T> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Const(self.0.clone())
+    }
+}
+
+impl<T: Num> ToDyn<T> for Zero<T> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Const(T::ZERO)
+    }
+}
+
+impl<T: Num> ToDyn<T> for One<T> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Const(T::ONE)
+    }
+}
+
+impl<T, Tag: Default + Debug, D> ToDyn<T> for var::Var<T, Tag, D> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Var(format!("{:?}", Tag::default()))
+    }
+}
+
+impl<T, Tag: Default + Debug, D> ToDyn<T> for var::Placeholder<T, Tag, D> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Var(format!("{:?}", Tag::default()))
+    }
+}
+
+macro_rules! to_dyn_unary {
+    ($($module:ident::$name:ident),*) => {$(
+        impl<T, X: ToDyn<T>> ToDyn<T> for $module::$name<X> {
+            #[inline]
+            fn to_dyn(&self) -> DynExpr<T> {
+                $name(Box::new(self.0.to_dyn()))
+            }
+        }
+    )*};
+}
+
+to_dyn_unary!(
+    ops::Neg,
+    elementary::Sin,
+    elementary::Cos,
+    elementary::Exp,
+    elementary::Ln,
+    elementary::Sqrt,
+    elementary::Tanh
+);
+
+macro_rules! to_dyn_binary {
+    ($($module:ident::$name:ident),*) => {$(
+        impl<T, L: ToDyn<T>, R: ToDyn<T>> ToDyn<T> for $module::$name<L, R> {
+            #[inline]
+            fn to_dyn(&self) -> DynExpr<T> {
+                $name(Box::new(self.0.to_dyn()), Box::new(self.1.to_dyn()))
+            }
+        }
+    )*};
+}
+
+to_dyn_binary!(
+    ops::Add,
+    ops::Sub,
+    ops::Mul,
+    ops::Div,
+    ops::Rem,
+    elementary::Powf,
+    cond::Less,
+    cond::LessEq,
+    cond::Greater,
+    cond::GreaterEq,
+    cond::Equal,
+    cond::NotEqual
+);
+
+impl<T, X: ToDyn<T>> ToDyn<T> for elementary::Powi<X> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Powi(Box::new(self.0.to_dyn()), self.1)
+    }
+}
+
+impl<T, X: ToDyn<T>, N: Int> ToDyn<T> for elementary::Pow<X, N> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Powi(Box::new(self.0.to_dyn()), N::VALUE.into())
+    }
+}
+
+impl<T, C: ToDyn<T>, A: ToDyn<T>, B: ToDyn<T>> ToDyn<T> for cond::Select<C, A, B> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Select(
+            Box::new(self.0.to_dyn()),
+            Box::new(self.1.to_dyn()),
+            Box::new(self.2.to_dyn()),
+        )
+    }
+}
+
+// only the first derivative of the function is kept, see DynFunc
+impl<T, F: DynDifferentiable<T>, X: ToDyn<T>> ToDyn<T> for call::Call<F, X> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Call(Box::new(F::dyn_func()), Box::new(self.1.to_dyn()))
+    }
+}
+
+impl<T, Tag: Default + Debug, V: ToDyn<T>, B: ToDyn<T>> ToDyn<T> for share::Let<Tag, V, B> {
+    #[inline]
+    fn to_dyn(&self) -> DynExpr<T> {
+        Let(
+            Box::new((format!("{:?}", Tag::default()), self.1.to_dyn())),
+            Box::new(self.2.to_dyn()),
+        )
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Parses infix formulas with `+ - * / %`, `^` for powers (integer literal exponents give
+/// `Powi`), unary minus, parentheses, numbers, variable names, the functions `sin`, `cos`, `exp`,
+/// `ln`, `sqrt` and `tanh`, a single comparison `< <= > >= == !=`, `select(cond, a, b)` and
+/// bindings `{ let name = value; body }`. Parentheses, function calls, bindings and unary minuses
+/// can be nested at most 256 deep, and so can the operations of the parsed expression, e.g. a sum
+/// has at most 256 terms.
+impl<T: FromStr> FromStr for DynExpr<T> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            src: s,
+            pos: 0,
+            depth: 0,
+            height: 0,
+        };
+        let e = parser.compare()?;
+        parser.skip_whitespace();
+        match parser.peek() {
+            None => Ok(e),
+            Some(c) => Err(parser.error(format!("unexpected `{}`", c))),
+        }
+    }
+}
+
+// the parser is recursive descent, so the nesting depth is limited to keep it on the stack, and
+// so is the height of the parsed expression, since evaluating, differentiating, printing and
+// dropping it recurse as deep
+const MAX_DEPTH: usize = 256;
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+    // height of the last parsed expression
+    height: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: String) -> ParseError {
+        ParseError {
+            position: self.pos,
+            message,
+        }
+    }
+
+    /// Set the height of a node whose highest operand has the given height, where `position` is
+    /// that of its operator.
+    fn node(&mut self, operands: usize, position: usize) -> Result<(), ParseError> {
+        if operands == MAX_DEPTH {
+            return Err(ParseError {
+                position,
+                message: "expression nested too deeply".to_string(),
+            });
+        }
+        self.height = operands + 1;
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skip whitespace and consume `c` if it's next.
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Skip whitespace and consume `s` if it's next.
+    fn eat_str(&mut self, s: &str) -> bool {
+        self.skip_whitespace();
+        if self.src[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !f(c) {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+        &self.src[start..self.pos]
+    }
+
+    // comparisons can't be chained, like in the printed form
+    fn compare<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        let l = Box::new(self.sum()?);
+        self.skip_whitespace();
+        let (op, height) = (self.pos, self.height);
+        let node: fn(_, _) -> _ = if self.eat_str("<=") {
+            LessEq
+        } else if self.eat_str(">=") {
+            GreaterEq
+        } else if self.eat_str("==") {
+            Equal
+        } else if self.eat_str("!=") {
+            NotEqual
+        } else if self.eat('<') {
+            Less
+        } else if self.eat('>') {
+            Greater
+        } else {
+            return Ok(*l);
+        };
+        let r = Box::new(self.sum()?);
+        self.node(height.max(self.height), op)?;
+        Ok(node(l, r))
+    }
+
+    fn sum<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        let mut e = self.product()?;
+        loop {
+            let node: fn(_, _) -> _ = if self.eat('+') {
+                Add
+            } else if self.eat('-') {
+                Sub
+            } else {
+                return Ok(e);
+            };
+            let (op, height) = (self.pos - 1, self.height);
+            let r = Box::new(self.product()?);
+            self.node(height.max(self.height), op)?;
+            e = node(Box::new(e), r);
+        }
+    }
+
+    fn product<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        let mut e = self.prefix()?;
+        loop {
+            let node: fn(_, _) -> _ = if self.eat('*') {
+                Mul
+            } else if self.eat('/') {
+                Div
+            } else if self.eat('%') {
+                Rem
+            } else {
+                return Ok(e);
+            };
+            let (op, height) = (self.pos - 1, self.height);
+            let r = Box::new(self.prefix()?);
+            self.node(height.max(self.height), op)?;
+            e = node(Box::new(e), r);
+        }
+    }
+
+    // every nested expression is parsed through here, so this is where the depth is tracked
+    fn prefix<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        if self.depth == MAX_DEPTH {
+            self.skip_whitespace();
+            return Err(self.error("expression nested too deeply".to_string()));
+        }
+        self.depth += 1;
+        let e = if self.eat('-') {
+            let op = self.pos - 1;
+            let x = Box::new(self.prefix()?);
+            self.node(self.height, op)?;
+            Neg(x)
+        } else {
+            self.power()?
+        };
+        self.depth -= 1;
+        Ok(e)
+    }
+
+    // exponentiation is right associative and binds stronger than a minus on its left, but the
+    // exponent itself may be negated, e.g. -x^-2 = -(x^(-2)) with the exponent as a Powi
+    fn power<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        let base = self.atom()?;
+        if !self.eat('^') {
+            return Ok(base);
+        }
+        let (op, height) = (self.pos - 1, self.height);
+        self.skip_whitespace();
+        let start = self.pos;
+        let exponent = self.prefix()?;
+        let literal = self.src[start..self.pos].trim();
+        match literal
+            .trim_matches(|c| c == '(' || c == ')')
+            .parse::<i64>()
+        {
+            Ok(n) => {
+                self.node(height, op)?;
+                Ok(Powi(Box::new(base), n))
+            }
+            Err(_) => {
+                self.node(height.max(self.height), op)?;
+                Ok(Powf(Box::new(base), Box::new(exponent)))
+            }
+        }
+    }
+
+    fn atom<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let e = self.compare()?;
+                if self.eat(')') {
+                    Ok(e)
+                } else {
+                    Err(self.error("expected `)`".to_string()))
+                }
+            }
+            Some('{') => {
+                self.pos += 1;
+                self.binding()
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(start),
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                if self.eat('(') {
+                    self.call(name, start)
+                } else {
+                    self.height = 1;
+                    Ok(Var(name.to_string()))
+                }
+            }
+            Some(c) => Err(self.error(format!("unexpected `{}`", c))),
+            None => Err(self.error("unexpected end of input".to_string())),
+        }
+    }
+
+    // bindings, numbers and calls are parsed out of line, which keeps the frames of the recursion
+    // small
+    fn binding<T: FromStr>(&mut self) -> Result<DynExpr<T>, ParseError> {
+        self.skip_whitespace();
+        if self.take_while(char::is_alphabetic) != "let" {
+            return Err(self.error("expected `let`".to_string()));
+        }
+        self.skip_whitespace();
+        let start = self.pos;
+        let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+        if !name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+            return Err(ParseError {
+                position: start,
+                message: "expected a name".to_string(),
+            });
+        }
+        if !self.eat('=') {
+            return Err(self.error("expected `=`".to_string()));
+        }
+        let value = self.compare()?;
+        if !self.eat(';') {
+            return Err(self.error("expected `;`".to_string()));
+        }
+        let height = self.height;
+        let body = self.compare()?;
+        if !self.eat('}') {
+            return Err(self.error("expected `}`".to_string()));
+        }
+        self.node(height.max(self.height), start)?;
+        Ok(Let(Box::new((name.to_string(), value)), Box::new(body)))
+    }
+
+    fn number<T: FromStr>(&mut self, start: usize) -> Result<DynExpr<T>, ParseError> {
+        self.take_while(|c| c.is_ascii_digit() || c == '.');
+        // exponent of a number in scientific notation
+        let rest = &self.src[self.pos..];
+        if rest.starts_with(['e', 'E']) {
+            let digits = rest[1..].strip_prefix(['+', '-']).unwrap_or(&rest[1..]);
+            if digits.starts_with(|c: char| c.is_ascii_digit()) {
+                self.pos += rest.len() - digits.len();
+                self.take_while(|c| c.is_ascii_digit());
+            }
+        }
+        self.height = 1;
+        match self.src[start..self.pos].parse() {
+            Ok(c) => Ok(Const(c)),
+            Err(_) => Err(ParseError {
+                position: start,
+                message: format!("invalid number `{}`", &self.src[start..self.pos]),
+            }),
+        }
+    }
+
+    fn call<T: FromStr>(&mut self, name: &str, start: usize) -> Result<DynExpr<T>, ParseError> {
+        let mut args = vec![Box::new(self.compare()?)];
+        let mut height = self.height;
+        while self.eat(',') {
+            args.push(Box::new(self.compare()?));
+            height = height.max(self.height);
+        }
+        if !self.eat(')') {
+            return Err(self.error("expected `)`".to_string()));
+        }
+        let arity = match name {
+            "sin" | "cos" | "exp" | "ln" | "sqrt" | "tanh" => 1,
+            "select" => 3,
+            _ => {
+                return Err(ParseError {
+                    position: start,
+                    message: format!("unknown function `{}`", name),
+                })
+            }
+        };
+        if args.len() != arity {
+            return Err(ParseError {
+                position: start,
+                message: format!("wrong number of arguments to `{}`", name),
+            });
+        }
+        self.node(height, start)?;
+        let mut args = args.into_iter();
+        let mut arg = || args.next().unwrap();
+        Ok(match name {
+            "sin" => Sin(arg()),
+            "cos" => Cos(arg()),
+            "exp" => Exp(arg()),
+            "ln" => Ln(arg()),
+            "sqrt" => Sqrt(arg()),
+            "tanh" => Tanh(arg()),
+            _ => Select(arg(), arg(), arg()),
+        })
+    }
+}
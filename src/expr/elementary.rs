@@ -0,0 +1,623 @@
+use crate::expr::constant::{Const, Scalar};
+use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::env::EvalWith;
+use crate::expr::ops::{Add, Div, Mul, Neg, Sub};
+use crate::expr::share::{ValueOf, ValueType};
+use crate::expr::subst::{Subst, SubstOut};
+use crate::expr::unit::{Unitless, UnitlessOut};
+use crate::expr::{Expr, Expression, One, Reduce, Reduced, Zero};
+use crate::int::{Decr, Int, IntDec, Pred, Succ, P1, Z0};
+use crate::num::{Float, Num};
+use std::convert::TryFrom;
+
+macro_rules! expr_elementary {
+    ($name:ident, $method:ident) => {
+        #[derive(Copy, Clone, Default, Debug)]
+        pub struct $name<T>(pub T);
+
+        impl<T> Expr<T> {
+            #[inline]
+            pub fn $method(self) -> Expr<$name<T>> {
+                Expr($name(self.0))
+            }
+        }
+
+        impl<T> Expression for $name<T>
+        where
+            T: Expression,
+            T::Output: Float,
+        {
+            type Output = T::Output;
+
+            #[inline(always)]
+            fn eval(self) -> Self::Output {
+                Float::$method(self.0.eval())
+            }
+        }
+
+        impl<T, Env> EvalWith<Env> for $name<T>
+        where
+            T: EvalWith<Env>,
+            T::Output: Float,
+        {
+            type Output = T::Output;
+
+            #[inline(always)]
+            fn eval_with(&self, env: &Env) -> Self::Output {
+                Float::$method(self.0.eval_with(env))
+            }
+        }
+
+        impl<T: Subst<Tag, R>, Tag, R> Subst<Tag, R> for $name<T> {
+            type Output = $name<SubstOut<T, Tag, R>>;
+
+            #[inline]
+            fn subst(self, replacement: &R) -> Self::Output {
+                $name(self.0.subst(replacement))
+            }
+        }
+
+        impl<T: Reduce> Reduce for $name<T> {
+            type Output = $name<Reduced<T>>;
+
+            #[inline]
+            fn reduce(self) -> Self::Output {
+                $name(self.0.reduce())
+            }
+        }
+    };
+}
+
+expr_elementary!(Sin, sin);
+expr_elementary!(Cos, cos);
+expr_elementary!(Exp, exp);
+expr_elementary!(Ln, ln);
+expr_elementary!(Sqrt, sqrt);
+expr_elementary!(Tanh, tanh);
+
+reduce_simple!({T} Sin<Zero<T>> => Zero<T>);
+reduce_simple!({T} Sin<One<T>> => Self);
+reduce_simple!({T} Cos<Zero<T>> => One<T>);
+reduce_simple!({T} Cos<One<T>> => Self);
+reduce_simple!({T} Exp<Zero<T>> => One<T>);
+reduce_simple!({T} Exp<One<T>> => Self);
+reduce_simple!({T} Ln<One<T>> => Zero<T>);
+// Unimplemented:
+//   - Ln<Zero>
+reduce_simple!({T} Sqrt<Zero<T>> => Zero<T>);
+reduce_simple!({T} Sqrt<One<T>> => One<T>);
+reduce_simple!({T} Tanh<Zero<T>> => Zero<T>);
+reduce_simple!({T} Tanh<One<T>> => Self);
+
+impl<Tag, T> Deriv<Tag> for Sin<T>
+where
+    T: Deriv<Tag> + Clone,
+    Mul<Cos<T>, Dv<T, Tag>>: Reduce,
+{
+    type Output = Reduced<Mul<Cos<T>, Dv<T, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Mul(Cos(self.0.clone()), self.0.deriv()).reduce()
+    }
+}
+
+impl<Tag, T> Deriv<Tag> for Cos<T>
+where
+    T: Deriv<Tag> + Clone,
+    Mul<Neg<Sin<T>>, Dv<T, Tag>>: Reduce,
+{
+    type Output = Reduced<Mul<Neg<Sin<T>>, Dv<T, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Mul(Neg(Sin(self.0.clone())), self.0.deriv()).reduce()
+    }
+}
+
+impl<Tag, T> Deriv<Tag> for Exp<T>
+where
+    T: Deriv<Tag> + Clone,
+    Mul<Exp<T>, Dv<T, Tag>>: Reduce,
+{
+    type Output = Reduced<Mul<Exp<T>, Dv<T, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Mul(self.clone(), self.0.deriv()).reduce()
+    }
+}
+
+impl<Tag, T> Deriv<Tag> for Ln<T>
+where
+    T: Deriv<Tag> + Clone,
+    Div<Dv<T, Tag>, T>: Reduce,
+{
+    type Output = Reduced<Div<Dv<T, Tag>, T>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Div(self.0.deriv(), self.0.clone()).reduce()
+    }
+}
+
+// d/dx sqrt(f) = f' / (sqrt(f) + sqrt(f)), which avoids needing a constant of the right type
+impl<Tag, T> Deriv<Tag> for Sqrt<T>
+where
+    T: Deriv<Tag> + Clone,
+    Div<Dv<T, Tag>, Add<Sqrt<T>, Sqrt<T>>>: Reduce,
+{
+    type Output = Reduced<Div<Dv<T, Tag>, Add<Sqrt<T>, Sqrt<T>>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Div(self.0.deriv(), Add(self.clone(), self.clone())).reduce()
+    }
+}
+
+// d/dx tanh(f) = f' - tanh(f)^2 f'
+impl<Tag, T> Deriv<Tag> for Tanh<T>
+where
+    T: Deriv<Tag> + Clone,
+    Dv<T, Tag>: Clone,
+    Mul<Mul<Tanh<T>, Tanh<T>>, Dv<T, Tag>>: Reduce,
+    Sub<Dv<T, Tag>, Reduced<Mul<Mul<Tanh<T>, Tanh<T>>, Dv<T, Tag>>>>: Reduce,
+{
+    #[rustfmt::skip]
+    #[allow(clippy::type_complexity)]
+    type Output = Reduced<Sub<
+        Dv<T, Tag>,
+        Reduced<Mul<Mul<Tanh<T>, Tanh<T>>, Dv<T, Tag>>>,
+    >>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        let d = self.0.deriv();
+        Sub(d.clone(), Mul(Mul(self.clone(), self.clone()), d).reduce()).reduce()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Integer power with an exponent known at runtime. The exponent is stored as an `i64`, so that
+/// the exponents of the derivatives of `x.powi(i32::MIN)` can be represented as well.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Powi<T>(pub T, pub i64);
+
+impl<T> Expr<T> {
+    #[inline]
+    pub fn powi(self, n: i32) -> Expr<Powi<T>> {
+        Expr(Powi(self.0, n.into()))
+    }
+}
+
+/// `x^n`, using `Float::powi` if the exponent fits in an `i32`.
+#[inline]
+pub(crate) fn powi<T: Float>(x: T, n: i64) -> T {
+    match i32::try_from(n) {
+        Ok(n) => x.powi(n),
+        Err(_) => pow_by_squaring(x, n),
+    }
+}
+
+impl<T> Expression for Powi<T>
+where
+    T: Expression,
+    T::Output: Float,
+{
+    type Output = T::Output;
+
+    #[inline(always)]
+    fn eval(self) -> Self::Output {
+        powi(self.0.eval(), self.1)
+    }
+}
+
+impl<T, Env> EvalWith<Env> for Powi<T>
+where
+    T: EvalWith<Env>,
+    T::Output: Float,
+{
+    type Output = T::Output;
+
+    #[inline(always)]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        powi(self.0.eval_with(env), self.1)
+    }
+}
+
+impl<T: Subst<Tag, R>, Tag, R> Subst<Tag, R> for Powi<T> {
+    type Output = Powi<SubstOut<T, Tag, R>>;
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        Powi(self.0.subst(replacement), self.1)
+    }
+}
+
+impl<T: Reduce> Reduce for Powi<T> {
+    type Output = Powi<Reduced<T>>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Powi(self.0.reduce(), self.1)
+    }
+}
+
+reduce_simple!({T} Powi<Zero<T>> => Self);
+reduce_simple!({T} Powi<One<T>> => One<T>);
+
+// d/dx f^n = n f^(n - 1) f', where the constant n has the same type as the expression, and the
+// derivative of f^0 is 0 f^0 rather than 0 f^-1, which isn't finite at zero
+impl<Tag, T> Deriv<Tag> for Powi<T>
+where
+    T: Deriv<Tag> + Clone + ValueType,
+    ValueOf<T>: Float,
+    Mul<Mul<Const<ValueOf<T>>, Powi<T>>, Dv<T, Tag>>: Reduce,
+{
+    type Output = Reduced<Mul<Mul<Const<ValueOf<T>>, Powi<T>>, Dv<T, Tag>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        let Powi(x, n) = self;
+        let m = if *n == 0 { 0 } else { n - 1 };
+        Mul(
+            Mul(Const(Float::from_i64(*n)), Powi(x.clone(), m)),
+            x.deriv(),
+        )
+        .reduce()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Integer power with the exponent `N` as a [type-level integer](../../int/trait.Int.html), which
+/// is evaluated by repeated squaring.
+/// # Examples
+/// ```
+/// use typing::expr::var::var;
+/// use typing::int::{N2, P1, P3};
+/// use typing::tags::X;
+///
+/// let x = var(2.0, X);
+/// assert_eq!(x.pow::<P3>().eval(), 8.0);
+/// assert_eq!(x.pow::<N2>().eval(), 0.25);
+///
+/// // the power rule with n = 3, reducing x^1 to x for the second derivative
+/// assert_eq!(x.pow::<P3>().deriv(X).eval(), 12.0);
+/// assert_eq!(x.pow::<P3>().deriv(X).deriv(X).to_string(), "3 * (2 * X)");
+/// assert_eq!(x.pow::<P1>().reduce().to_string(), "X");
+/// ```
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Pow<T, N>(pub T, pub N);
+
+impl<T> Expr<T> {
+    #[inline]
+    pub fn pow<N: Int>(self) -> Expr<Pow<T, N>> {
+        Expr(Pow(self.0, N::default()))
+    }
+}
+
+/// `x^n` with `O(log n)` multiplications.
+#[inline(always)]
+pub(crate) fn pow_by_squaring<T>(mut x: T, n: i64) -> T
+where
+    T: Num + std::ops::Mul<Output = T> + std::ops::Div<Output = T>,
+{
+    let mut k = n.unsigned_abs();
+    let mut acc = T::ONE;
+    while k > 0 {
+        if k & 1 == 1 {
+            acc = acc * x;
+        }
+        k >>= 1;
+        if k > 0 {
+            x = x * x;
+        }
+    }
+    if n < 0 {
+        T::ONE / acc
+    } else {
+        acc
+    }
+}
+
+/// Values that can be raised to the type-level integer power `N`, which may change their type,
+/// e.g. for [quantities](../unit/struct.Quantity.html).
+pub trait IntPow<N> {
+    type Output;
+    fn int_pow(self) -> Self::Output;
+}
+
+pub type IntPowOut<T, N> = <T as IntPow<N>>::Output;
+
+impl<T, N: Int> IntPow<N> for T
+where
+    T: Scalar + Num + std::ops::Mul<Output = T> + std::ops::Div<Output = T>,
+{
+    type Output = T;
+
+    #[inline(always)]
+    fn int_pow(self) -> Self::Output {
+        pow_by_squaring(self, N::VALUE.into())
+    }
+}
+
+impl<T: Expression, N> Expression for Pow<T, N>
+where
+    T::Output: IntPow<N>,
+{
+    type Output = IntPowOut<T::Output, N>;
+
+    #[inline(always)]
+    fn eval(self) -> Self::Output {
+        self.0.eval().int_pow()
+    }
+}
+
+impl<T: EvalWith<Env>, N, Env> EvalWith<Env> for Pow<T, N>
+where
+    T::Output: IntPow<N>,
+{
+    type Output = IntPowOut<T::Output, N>;
+
+    #[inline(always)]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        self.0.eval_with(env).int_pow()
+    }
+}
+
+impl<T: Subst<Tag, R>, N, Tag, R> Subst<Tag, R> for Pow<T, N> {
+    type Output = Pow<SubstOut<T, Tag, R>, N>;
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        Pow(self.0.subst(replacement), self.1)
+    }
+}
+
+// the exponents 0 and 1 are folded, so the general case is split into exponents of at least 2
+// and negative ones to avoid overlapping impls
+impl<T: Reduce, N> Reduce for Pow<T, Succ<Succ<N>>> {
+    type Output = Pow<Reduced<T>, Succ<Succ<N>>>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Pow(self.0.reduce(), self.1)
+    }
+}
+
+impl<T: Reduce, N> Reduce for Pow<T, Pred<N>> {
+    type Output = Pow<Reduced<T>, Pred<N>>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Pow(self.0.reduce(), self.1)
+    }
+}
+
+// powers of zeros and ones keep the type of the power, e.g. the dimension of a quantity
+impl<T: ValueType> Reduce for Pow<T, Z0>
+where
+    ValueOf<T>: IntPow<Z0>,
+{
+    type Output = One<IntPowOut<ValueOf<T>, Z0>>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        One::new()
+    }
+}
+
+impl<T: Reduce> Reduce for Pow<T, P1> {
+    type Output = Reduced<T>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        self.0.reduce()
+    }
+}
+
+reduce_simple!({T, N} Pow<Zero<T>, Pred<N>> => Self);
+reduce_simple!({T} Pow<Zero<T>, P1> => Zero<T>);
+reduce_simple!({T} Pow<One<T>, P1> => One<T>);
+
+macro_rules! reduce_pow_identity {
+    ($($name:ident<T>, $n:ty;)*) => {$(
+        impl<T: IntPow<$n>, N> Reduce for Pow<$name<T>, $n> {
+            type Output = $name<IntPowOut<T, $n>>;
+
+            #[inline(always)]
+            fn reduce(self) -> Self::Output {
+                $name::new()
+            }
+        }
+    )*};
+}
+
+reduce_pow_identity!(
+    Zero<T>, Succ<Succ<N>>;
+    One<T>, Succ<Succ<N>>;
+    One<T>, Pred<N>;
+);
+
+// d/dx f^N = N f^(N - 1) f', where the constant N is dimensionless, split like Reduce since the
+// derivative of f^0 is zero
+macro_rules! deriv_pow {
+    ($($n:ty),*) => {$(
+        impl<Tag, T, N> Deriv<Tag> for Pow<T, $n>
+        where
+            T: Deriv<Tag> + Clone + ValueType,
+            ValueOf<T>: Unitless,
+            UnitlessOut<ValueOf<T>>: Float,
+            $n: Int + IntDec,
+            Pow<T, Decr<$n>>: Reduce,
+            Mul<Mul<Const<UnitlessOut<ValueOf<T>>>, Reduced<Pow<T, Decr<$n>>>>, Dv<T, Tag>>: Reduce,
+        {
+            #[allow(clippy::type_complexity)]
+            type Output = Reduced<
+                Mul<Mul<Const<UnitlessOut<ValueOf<T>>>, Reduced<Pow<T, Decr<$n>>>>, Dv<T, Tag>>,
+            >;
+
+            #[inline]
+            fn deriv(&self) -> Self::Output {
+                let x = &self.0;
+                let n = Const(Float::from_i32(<$n>::VALUE));
+                Mul(
+                    Mul(n, Pow(x.clone(), Decr::<$n>::default()).reduce()),
+                    x.deriv(),
+                )
+                .reduce()
+            }
+        }
+    )*};
+}
+
+deriv_pow!(Succ<N>, Pred<N>);
+
+impl<Tag, T: ValueType> Deriv<Tag> for Pow<T, Z0>
+where
+    ValueOf<T>: IntPow<Z0>,
+{
+    type Output = Zero<IntPowOut<ValueOf<T>, Z0>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Zero::new()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Power with an arbitrary expression as the exponent.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Powf<L, R>(pub L, pub R);
+
+impl<L> Expr<L> {
+    #[inline]
+    pub fn powf<R>(self, exponent: Expr<R>) -> Expr<Powf<L, R>> {
+        Expr(Powf(self.0, exponent.0))
+    }
+}
+
+impl<L, R> Expression for Powf<L, R>
+where
+    L: Expression,
+    R: Expression<Output = L::Output>,
+    L::Output: Float,
+{
+    type Output = L::Output;
+
+    #[inline(always)]
+    fn eval(self) -> Self::Output {
+        self.0.eval().powf(self.1.eval())
+    }
+}
+
+impl<L, R, Env> EvalWith<Env> for Powf<L, R>
+where
+    L: EvalWith<Env>,
+    R: EvalWith<Env, Output = L::Output>,
+    L::Output: Float,
+{
+    type Output = L::Output;
+
+    #[inline(always)]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        self.0.eval_with(env).powf(self.1.eval_with(env))
+    }
+}
+
+impl<L, R, Tag, X> Subst<Tag, X> for Powf<L, R>
+where
+    L: Subst<Tag, X>,
+    R: Subst<Tag, X>,
+{
+    type Output = Powf<SubstOut<L, Tag, X>, SubstOut<R, Tag, X>>;
+
+    #[inline]
+    fn subst(self, replacement: &X) -> Self::Output {
+        Powf(self.0.subst(replacement), self.1.subst(replacement))
+    }
+}
+
+impl<L: Reduce, R: Reduce> Reduce for Powf<L, R> {
+    type Output = Powf<Reduced<L>, Reduced<R>>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Powf(self.0.reduce(), self.1.reduce())
+    }
+}
+
+impl<L: Reduce, T> Reduce for Powf<L, One<T>> {
+    type Output = Reduced<L>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        self.0.reduce()
+    }
+}
+
+impl<L: Reduce, T> Reduce for Powf<L, Zero<T>> {
+    type Output = One<T>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        One::new()
+    }
+}
+
+impl<T, R: Reduce> Reduce for Powf<One<T>, R> {
+    type Output = One<T>;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        self.0
+    }
+}
+
+reduce_simple!({A, B} Powf<Zero<A>, One<B>> => Zero<A>);
+reduce_simple!({A, B} Powf<One<A>, One<B>> => One<A>);
+reduce_simple!({A, B} Powf<One<A>, Zero<B>> => One<A>);
+// 0^0 is taken to be 1, as with `powf`
+reduce_simple!({A, B} Powf<Zero<A>, Zero<B>> => One<A>);
+
+// d/dx f^g = f^g (g' ln(f) + g f' / f)
+impl<Tag, L, R> Deriv<Tag> for Powf<L, R>
+where
+    L: Deriv<Tag> + Clone,
+    R: Deriv<Tag> + Clone,
+    Mul<Dv<R, Tag>, Ln<L>>: Reduce,
+    Div<Dv<L, Tag>, L>: Reduce,
+    Mul<R, Reduced<Div<Dv<L, Tag>, L>>>: Reduce,
+    Add<Reduced<Mul<Dv<R, Tag>, Ln<L>>>, Reduced<Mul<R, Reduced<Div<Dv<L, Tag>, L>>>>>: Reduce,
+    Mul<
+        Powf<L, R>,
+        Reduced<Add<Reduced<Mul<Dv<R, Tag>, Ln<L>>>, Reduced<Mul<R, Reduced<Div<Dv<L, Tag>, L>>>>>>,
+    >: Reduce,
+{
+    #[rustfmt::skip]
+    #[allow(clippy::type_complexity)]
+    type Output = Reduced<Mul<
+        Powf<L, R>,
+        Reduced<Add<
+            Reduced<Mul<Dv<R, Tag>, Ln<L>>>,
+            Reduced<Mul<R, Reduced<Div<Dv<L, Tag>, L>>>>,
+        >>,
+    >>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        let Powf(l, r) = self;
+        Mul(
+            self.clone(),
+            Add(
+                Mul(r.deriv(), Ln(l.clone())).reduce(),
+                Mul(r.clone(), Div(l.deriv(), l.clone()).reduce()).reduce(),
+            )
+            .reduce(),
+        )
+        .reduce()
+    }
+}
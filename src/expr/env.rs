@@ -0,0 +1,38 @@
+use crate::expr::Expr;
+
+/// Evaluation of an expression using the values bound to placeholder variables in an
+/// environment, which is a labelled paren of `(tag, value)` pairs. Unlike [`Expression`], this
+/// doesn't consume the expression so it can be evaluated repeatedly.
+///
+/// [`Expression`]: ../trait.Expression.html
+pub trait EvalWith<Env> {
+    type Output;
+    fn eval_with(&self, env: &Env) -> Self::Output;
+}
+
+pub type EvalWithOut<T, Env> = <T as EvalWith<Env>>::Output;
+
+impl<T: EvalWith<Env>, Env> EvalWith<Env> for Expr<T> {
+    type Output = T::Output;
+
+    #[inline]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        self.0.eval_with(env)
+    }
+}
+
+impl<A: EvalWith<Env>, B: EvalWith<Env>, Env> EvalWith<Env> for (A, B) {
+    type Output = (A::Output, B::Output);
+
+    #[inline]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        (self.0.eval_with(env), self.1.eval_with(env))
+    }
+}
+
+impl<Env> EvalWith<Env> for () {
+    type Output = ();
+
+    #[inline(always)]
+    fn eval_with(&self, _: &Env) -> Self::Output {}
+}
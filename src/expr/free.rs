@@ -0,0 +1,248 @@
+use crate::boolean::{BoolOr, False, Or, True};
+use crate::expr::call::Call;
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{Cos, Exp, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use crate::expr::share::Let;
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, One, Zero};
+use crate::ident::{IsEq, TypeEq};
+
+/// Whether a paren of tags contains `Tag`.
+#[doc(hidden)]
+pub trait Contains<Tag> {
+    type Output;
+}
+
+impl<Tag> Contains<Tag> for () {
+    type Output = False;
+}
+
+impl<Tag, K, B> Contains<Tag> for (K, B)
+where
+    K: TypeEq<Tag>,
+    B: Contains<Tag>,
+    IsEq<K, Tag>: BoolOr<<B as Contains<Tag>>::Output>,
+{
+    type Output = Or<IsEq<K, Tag>, <B as Contains<Tag>>::Output>;
+}
+
+/// Append `Tag` to a paren of tags unless it's already in there.
+#[doc(hidden)]
+pub trait Insert<Tag> {
+    type Output;
+}
+
+#[doc(hidden)]
+pub trait Insert2<Tag, Marker> {
+    type Output;
+}
+
+impl<Tag> Insert<Tag> for () {
+    type Output = (Tag, ());
+}
+
+impl<Tag, K, B> Insert<Tag> for (K, B)
+where
+    K: TypeEq<Tag>,
+    Self: Insert2<Tag, IsEq<K, Tag>>,
+{
+    type Output = <Self as Insert2<Tag, IsEq<K, Tag>>>::Output;
+}
+
+impl<Tag, K, B> Insert2<Tag, True> for (K, B) {
+    type Output = Self;
+}
+
+impl<Tag, K, B: Insert<Tag>> Insert2<Tag, False> for (K, B) {
+    type Output = (K, <B as Insert<Tag>>::Output);
+}
+
+/// Remove `Tag` from a paren of distinct tags.
+#[doc(hidden)]
+pub trait Remove<Tag> {
+    type Output;
+}
+
+#[doc(hidden)]
+pub trait Remove2<Tag, Marker> {
+    type Output;
+}
+
+impl<Tag> Remove<Tag> for () {
+    type Output = ();
+}
+
+impl<Tag, K, B> Remove<Tag> for (K, B)
+where
+    K: TypeEq<Tag>,
+    Self: Remove2<Tag, IsEq<K, Tag>>,
+{
+    type Output = <Self as Remove2<Tag, IsEq<K, Tag>>>::Output;
+}
+
+impl<Tag, K, B> Remove2<Tag, True> for (K, B) {
+    type Output = B;
+}
+
+impl<Tag, K, B: Remove<Tag>> Remove2<Tag, False> for (K, B) {
+    type Output = (K, <B as Remove<Tag>>::Output);
+}
+
+/// Tags of `Self` followed by the ones of `R` that aren't in `Self`.
+#[doc(hidden)]
+pub trait Union<R> {
+    type Output;
+}
+
+impl<S> Union<()> for S {
+    type Output = S;
+}
+
+impl<S: Insert<K>, K, B> Union<(K, B)> for S
+where
+    <S as Insert<K>>::Output: Union<B>,
+{
+    type Output = <<S as Insert<K>>::Output as Union<B>>::Output;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// The distinct tags of the variables and placeholders in an expression, as a paren in the order
+/// of their first occurrence.
+pub trait FreeVars {
+    type Output;
+}
+
+pub type FreeVarsOut<T> = <T as FreeVars>::Output;
+
+/// Whether an expression contains a variable or placeholder tagged with `Tag`, as a
+/// [`True`](../../boolean/type.True.html) or [`False`](../../boolean/type.False.html).
+pub trait DependsOn<Tag> {
+    type Output;
+}
+
+pub type DependsOnOut<T, Tag> = <T as DependsOn<Tag>>::Output;
+
+impl<T: FreeVars, Tag> DependsOn<Tag> for T
+where
+    FreeVarsOut<T>: Contains<Tag>,
+{
+    type Output = <FreeVarsOut<T> as Contains<Tag>>::Output;
+}
+
+impl<T: FreeVars> FreeVars for Expr<T> {
+    type Output = T::Output;
+}
+
+impl<T, Tag, D> FreeVars for Var<T, Tag, D> {
+    type Output = (Tag, ());
+}
+
+impl<T, Tag, D> FreeVars for Placeholder<T, Tag, D> {
+    type Output = (Tag, ());
+}
+
+impl<T> FreeVars for Const<T> {
+    type Output = ();
+}
+
+impl<T> FreeVars for Zero<T> {
+    type Output = ();
+}
+
+impl<T> FreeVars for One<T> {
+    type Output = ();
+}
+
+impl<T: FreeVars> FreeVars for Powi<T> {
+    type Output = T::Output;
+}
+
+impl<T: FreeVars, N> FreeVars for Pow<T, N> {
+    type Output = T::Output;
+}
+
+impl<F, T: FreeVars> FreeVars for Call<F, T> {
+    type Output = T::Output;
+}
+
+macro_rules! free_vars_unary {
+    ($($name:ident)*) => {$(
+        impl<T: FreeVars> FreeVars for $name<T> {
+            type Output = T::Output;
+        }
+    )*};
+}
+
+free_vars_unary!(Neg Not Sin Cos Exp Ln Sqrt Tanh);
+
+macro_rules! free_vars_binary {
+    ($($name:ident)*) => {$(
+        impl<L: FreeVars, R: FreeVars> FreeVars for $name<L, R>
+        where
+            L::Output: Union<R::Output>,
+        {
+            type Output = <L::Output as Union<R::Output>>::Output;
+        }
+    )*};
+}
+
+free_vars_binary!(Add BitAnd BitOr BitXor Div Mul Rem Shl Shr Sub Powf);
+free_vars_binary!(Less LessEq Greater GreaterEq Equal NotEqual);
+
+impl<C: FreeVars, A: FreeVars, B: FreeVars> FreeVars for Select<C, A, B>
+where
+    C::Output: Union<A::Output>,
+    <C::Output as Union<A::Output>>::Output: Union<B::Output>,
+{
+    type Output = <<C::Output as Union<A::Output>>::Output as Union<B::Output>>::Output;
+}
+
+// the bound tag isn't free in the body
+impl<Tag, V: FreeVars, B: FreeVars> FreeVars for Let<Tag, V, B>
+where
+    B::Output: Remove<Tag>,
+    V::Output: Union<<B::Output as Remove<Tag>>::Output>,
+{
+    type Output = <V::Output as Union<<B::Output as Remove<Tag>>::Output>>::Output;
+}
+
+impl<T> Expr<T> {
+    /// The tags of the variables in the expression, e.g. to get the full gradient.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::expr::Expression;
+    /// use typing::tags::{X, Y, Z};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    /// let f = (y * x).sin() + x * 4.0;
+    ///
+    /// let _: typing::Paren!(Y, X) = f.free_vars();
+    /// let (dy, (dx, ())) = f.grad(f.free_vars()).eval();
+    /// assert_eq!((dx, dy), (6f64.cos() * 3.0 + 4.0, 6f64.cos() * 2.0));
+    /// assert_eq!(bool::from(f.depends_on(X)), true);
+    /// assert_eq!(bool::from(f.depends_on(Z)), false);
+    /// ```
+    #[inline]
+    pub fn free_vars(&self) -> FreeVarsOut<T>
+    where
+        T: FreeVars,
+        FreeVarsOut<T>: Default,
+    {
+        Default::default()
+    }
+
+    /// Whether the expression depends on the variable tagged with `Tag`, as a type-level boolean.
+    #[inline]
+    pub fn depends_on<Tag>(&self, _: Tag) -> DependsOnOut<T, Tag>
+    where
+        T: DependsOn<Tag>,
+        DependsOnOut<T, Tag>: Default,
+    {
+        Default::default()
+    }
+}
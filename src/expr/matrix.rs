@@ -0,0 +1,296 @@
+use crate::expr::constant::{Const, Scalar};
+use crate::expr::ops::{Add, Mul, Neg, Sub};
+use crate::expr::Expression;
+use crate::num::Num;
+use std::ops::Index;
+
+/// Dense matrix with `R` rows and `C` columns, stored row by row.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Matrix<T, const R: usize, const C: usize>(pub [[T; C]; R]);
+
+/// Dense column vector with `N` elements.
+pub type Vector<T, const N: usize> = Matrix<T, N, 1>;
+
+impl<T, const R: usize, const C: usize> Index<(usize, usize)> for Matrix<T, R, C> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, (i, j): (usize, usize)) -> &Self::Output {
+        &self.0[i][j]
+    }
+}
+
+/// Lazy matrix expression with `R` rows and `C` columns, where the operators only build the
+/// expression tree and check the dimensions at compile time. The elements are computed when the
+/// expression is evaluated, except for the operands of matrix products: every element of the
+/// product reads a whole row and column of them, so they're evaluated into matrices when the
+/// product is built, and a chain of products costs as much as multiplying its factors in turn.
+/// # Examples
+/// ```
+/// use typing::expr::matrix::{column, matrix, Matrix};
+///
+/// let a = matrix([[1, 2, 3], [4, 5, 6]]);
+/// let x = column([1, 0, -1]);
+/// assert_eq!((a * x).eval(), Matrix([[-2], [-2]]));
+/// assert_eq!((a.transpose() * 2 - a.transpose()).eval(), Matrix([[1, 4], [2, 5], [3, 6]]));
+/// ```
+///
+/// The dimensions of the operands have to match:
+/// ```compile_fail
+/// use typing::expr::matrix::matrix;
+///
+/// let a = matrix([[1, 2, 3], [4, 5, 6]]);
+/// let _ = a * a;
+/// ```
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Mat<E, const R: usize, const C: usize>(pub E);
+
+/// Create a matrix expression from its rows.
+#[inline]
+pub fn matrix<T, const R: usize, const C: usize>(rows: [[T; C]; R]) -> Mat<Matrix<T, R, C>, R, C> {
+    Mat(Matrix(rows))
+}
+
+/// Create a column vector expression from its elements.
+#[inline]
+pub fn column<T, const N: usize>(values: [T; N]) -> Mat<Vector<T, N>, N, 1> {
+    Mat(Matrix(values.map(|x| [x])))
+}
+
+/// Element access of a matrix expression node.
+pub trait MatrixExpr {
+    type Item;
+
+    /// The number of rows and columns, which the product nodes need for their inner dimension.
+    fn shape(&self) -> (usize, usize);
+
+    /// The element in row `i` and column `j`.
+    fn at(&self, i: usize, j: usize) -> Self::Item;
+}
+
+/// Transpose of a matrix expression.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Transpose<T>(pub T);
+
+impl<E: MatrixExpr, const R: usize, const C: usize> Mat<E, R, C> {
+    /// Evaluate the elements of the expression into a matrix.
+    #[inline]
+    pub fn eval(self) -> Matrix<E::Item, R, C> {
+        Matrix(std::array::from_fn(|i| {
+            std::array::from_fn(|j| self.0.at(i, j))
+        }))
+    }
+}
+
+impl<E: MatrixExpr, const R: usize, const C: usize> Expression for Mat<E, R, C> {
+    type Output = Matrix<E::Item, R, C>;
+
+    #[inline]
+    fn eval(self) -> Self::Output {
+        Mat::eval(self)
+    }
+}
+
+impl<E, const R: usize, const C: usize> Mat<E, R, C> {
+    #[inline]
+    pub fn transpose(self) -> Mat<Transpose<E>, C, R> {
+        Mat(Transpose(self.0))
+    }
+}
+
+impl<T: Clone, const R: usize, const C: usize> MatrixExpr for Matrix<T, R, C> {
+    type Item = T;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        (R, C)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        self.0[i][j].clone()
+    }
+}
+
+impl<T: MatrixExpr> MatrixExpr for Transpose<T> {
+    type Item = T::Item;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        let (rows, cols) = self.0.shape();
+        (cols, rows)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        self.0.at(j, i)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<E, const R: usize, const C: usize> std::ops::Neg for Mat<E, R, C> {
+    type Output = Mat<Neg<E>, R, C>;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Mat(Neg(self.0))
+    }
+}
+
+impl<T> MatrixExpr for Neg<T>
+where
+    T: MatrixExpr,
+    T::Item: std::ops::Neg,
+{
+    type Item = <T::Item as std::ops::Neg>::Output;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        self.0.shape()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        -self.0.at(i, j)
+    }
+}
+
+macro_rules! matrix_elementwise {
+    ($($name:ident, $method:ident;)*) => {$(
+        impl<L, Rhs, const R: usize, const C: usize> std::ops::$name<Mat<Rhs, R, C>>
+            for Mat<L, R, C>
+        {
+            type Output = Mat<$name<L, Rhs>, R, C>;
+
+            #[inline]
+            fn $method(self, rhs: Mat<Rhs, R, C>) -> Self::Output {
+                Mat($name(self.0, rhs.0))
+            }
+        }
+
+        impl<L, R> MatrixExpr for $name<L, R>
+        where
+            L: MatrixExpr,
+            R: MatrixExpr,
+            L::Item: std::ops::$name<R::Item>,
+        {
+            type Item = <L::Item as std::ops::$name<R::Item>>::Output;
+
+            #[inline(always)]
+            fn shape(&self) -> (usize, usize) {
+                self.0.shape()
+            }
+
+            #[inline(always)]
+            fn at(&self, i: usize, j: usize) -> Self::Item {
+                std::ops::$name::$method(self.0.at(i, j), self.1.at(i, j))
+            }
+        }
+    )*};
+}
+
+matrix_elementwise!(
+    Add, add;
+    Sub, sub;
+);
+
+// matrix product, where the inner dimension is the number of columns of the left hand side; the
+// operands are evaluated first, since recomputing them for every element of a chain of products
+// would multiply the cost by the inner dimension for every factor
+impl<L, Rhs, const R: usize, const K: usize, const C: usize> std::ops::Mul<Mat<Rhs, K, C>>
+    for Mat<L, R, K>
+where
+    L: MatrixExpr,
+    Rhs: MatrixExpr<Item = L::Item>,
+{
+    type Output = Mat<Mul<Matrix<L::Item, R, K>, Matrix<L::Item, K, C>>, R, C>;
+
+    #[inline]
+    fn mul(self, rhs: Mat<Rhs, K, C>) -> Self::Output {
+        Mat(Mul(self.eval(), rhs.eval()))
+    }
+}
+
+impl<L, R> MatrixExpr for Mul<L, R>
+where
+    L: MatrixExpr,
+    R: MatrixExpr<Item = L::Item>,
+    L::Item: Num + std::ops::Add<Output = L::Item> + std::ops::Mul<Output = L::Item>,
+{
+    type Item = L::Item;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        (self.0.shape().0, self.1.shape().1)
+    }
+
+    #[inline]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        (0..self.0.shape().1).fold(L::Item::ZERO, |sum, k| {
+            sum + self.0.at(i, k) * self.1.at(k, j)
+        })
+    }
+}
+
+// products with scalars, which are scaling the elements
+impl<E, T: Scalar, const R: usize, const C: usize> std::ops::Mul<T> for Mat<E, R, C> {
+    type Output = Mat<Mul<E, Const<T>>, R, C>;
+
+    #[inline]
+    fn mul(self, rhs: T) -> Self::Output {
+        Mat(Mul(self.0, Const(rhs)))
+    }
+}
+
+impl<L, T> MatrixExpr for Mul<L, Const<T>>
+where
+    L: MatrixExpr,
+    L::Item: std::ops::Mul<T>,
+    T: Clone,
+{
+    type Item = <L::Item as std::ops::Mul<T>>::Output;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        self.0.shape()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        self.0.at(i, j) * self.1 .0.clone()
+    }
+}
+
+macro_rules! matrix_scalar_mul {
+    ($($t:ty)*) => {$(
+        impl<E, const R: usize, const C: usize> std::ops::Mul<Mat<E, R, C>> for $t {
+            type Output = Mat<Mul<Const<$t>, E>, R, C>;
+
+            #[inline]
+            fn mul(self, rhs: Mat<E, R, C>) -> Self::Output {
+                Mat(Mul(Const(self), rhs.0))
+            }
+        }
+    )*};
+}
+
+matrix_scalar_mul!(f32 f64 i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+impl<T, R> MatrixExpr for Mul<Const<T>, R>
+where
+    T: Clone + std::ops::Mul<R::Item>,
+    R: MatrixExpr,
+{
+    type Item = <T as std::ops::Mul<R::Item>>::Output;
+
+    #[inline(always)]
+    fn shape(&self) -> (usize, usize) {
+        self.1.shape()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, j: usize) -> Self::Item {
+        self.0 .0.clone() * self.1.at(i, j)
+    }
+}
@@ -1,8 +1,53 @@
-use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::deriv::{Deriv, Dv, Grad, GradOut, HessianOut, Jacobian};
+use crate::expr::env::{EvalWith, EvalWithOut};
+use crate::expr::simplify::{Simplified, Simplify};
+use crate::expr::subst::{Subst, SubstOut};
+use crate::num::Num;
+use crate::paren::ops::{Zip, ZipOut};
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
 
+// simple reductions for the expression node modules
+macro_rules! reduce_simple {
+    ($({$($c:tt $(: $c0:ident $(+ $cN:ident)*)?),*})? $typ:ty => Self) => {
+        impl<$($($c $(: $c0 $(+ $cN)*)?,)*)?>  Reduce for $typ {
+            type Output = Self;
+            #[inline(always)]
+            fn reduce(self) -> Self::Output {
+                self
+            }
+        }
+    };
+    ($({$($c:tt $(: $c0:ident $(+ $cN:ident)*)?),*})? $typ:ty => $ret:ty) => {
+        impl<$($($c $(: $c0 $(+ $cN)*)?,)*)?>  Reduce for $typ {
+            type Output = $ret;
+            #[inline(always)]
+            fn reduce(self) -> Self::Output {
+                Default::default()
+            }
+        }
+    };
+}
+
+pub mod call;
+pub mod cond;
+pub mod constant;
 pub mod deriv;
+pub mod dual;
+pub mod dynamic;
+pub mod elementary;
+pub mod env;
+pub mod free;
+pub mod matrix;
 pub mod ops;
+pub mod print;
+pub mod share;
+pub mod simplify;
+pub mod subst;
+pub mod tape;
+pub mod unit;
 pub mod var;
+pub mod vector;
 
 pub trait Expression {
     type Output;
@@ -13,11 +58,88 @@ pub type ExprOut<T> = <T as Expression>::Output;
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Copy, Clone, Debug, Default)]
-pub struct Zero;
+macro_rules! identity_expr {
+    ($(#[$meta:meta])* $name:ident, $value:ident) => {
+        $(#[$meta])*
+        pub struct $name<T>(PhantomData<*const T>);
+
+        impl<T> $name<T> {
+            #[inline]
+            pub fn new() -> Self {
+                Self(PhantomData)
+            }
+        }
+
+        impl<T> Debug for $name<T> {
+            fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+                f.write_str(stringify!($name))
+            }
+        }
+
+        impl<T> Clone for $name<T> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T> Copy for $name<T> {}
+
+        impl<T> Default for $name<T> {
+            #[inline]
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl<T: Num> Expression for $name<T> {
+            type Output = T;
+
+            #[inline]
+            fn eval(self) -> Self::Output {
+                T::$value
+            }
+        }
+
+        impl<T: Num, Env> EvalWith<Env> for $name<T> {
+            type Output = T;
+
+            #[inline]
+            fn eval_with(&self, _: &Env) -> Self::Output {
+                T::$value
+            }
+        }
+
+        impl<T, Tag, R> Subst<Tag, R> for $name<T> {
+            type Output = Self;
 
-#[derive(Copy, Clone, Debug, Default)]
-pub struct One;
+            #[inline(always)]
+            fn subst(self, _: &R) -> Self::Output {
+                self
+            }
+        }
+
+        impl<T, Tag> Deriv<Tag> for $name<T> {
+            type Output = Zero<T>;
+
+            #[inline]
+            fn deriv(&self) -> Self::Output {
+                Zero::new()
+            }
+        }
+    };
+}
+
+identity_expr!(
+    /// The additive identity, evaluating to zero of the numeric type `T`.
+    Zero,
+    ZERO
+);
+identity_expr!(
+    /// The multiplicative identity, evaluating to one of the numeric type `T`.
+    One,
+    ONE
+);
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -48,6 +170,118 @@ impl<T> Expr<T> {
         Expr(self.0.deriv())
     }
 
+    /// Evaluate the expression using the values bound to placeholder variables in a labelled
+    /// paren, see [`EvalWith`](env/trait.EvalWith.html).
+    #[inline]
+    pub fn eval_with<Env>(&self, env: &Env) -> EvalWithOut<T, Env>
+    where
+        T: EvalWith<Env>,
+    {
+        self.0.eval_with(env)
+    }
+
+    /// Turn the expression into a closure taking a paren of values, which are bound to the
+    /// placeholder variables in the order given by a paren of tags. The environment is built by
+    /// zipping the tags with the arguments, so no lookup happens at runtime.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::placeholder;
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = placeholder::<f64, _>(X);
+    /// let y = placeholder::<f64, _>(Y);
+    /// let f = (x * y + x).compile(paren!(Y, X));
+    ///
+    /// assert_eq!(f(paren!(2.0, 3.0)), 9.0);
+    /// assert_eq!((0..4).map(|i| f(paren!(i as f64, 1.0))).sum::<f64>(), 10.0);
+    /// ```
+    #[inline]
+    pub fn compile<Tags, Args>(
+        self,
+        tags: Tags,
+    ) -> impl Fn(Args) -> EvalWithOut<T, ZipOut<Tags, Args>>
+    where
+        Tags: Zip<Args> + Copy,
+        T: EvalWith<ZipOut<Tags, Args>>,
+    {
+        move |args| self.0.eval_with(&tags.zip(args))
+    }
+
+    /// Replace every variable tagged with `Tag`, including placeholders, by another expression.
+    /// # Examples
+    /// ```
+    /// use typing::expr::{var::var, Expression};
+    /// use typing::tags::{T, X};
+    ///
+    /// let x = var(0.0, X);
+    /// let t = var(2.0, T);
+    /// let f = x * x + x;
+    /// let g = f.subst(X, t * 3.0);
+    ///
+    /// assert_eq!(g.eval(), 42.0);
+    /// assert_eq!(g.deriv(T).eval(), 39.0);
+    /// ```
+    #[inline]
+    pub fn subst<Tag, R>(self, _: Tag, replacement: Expr<R>) -> Expr<SubstOut<T, Tag, R>>
+    where
+        T: Subst<Tag, R>,
+    {
+        Expr(self.0.subst(&replacement.0))
+    }
+
+    /// Derivatives with respect to each tag in a paren of tags.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::{var::var, Expression};
+    /// use typing::tags::{X, Y, Z};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    ///
+    /// let f = x * x * y;
+    /// assert_eq!(f.grad(paren!(X, Y, Z)).eval(), paren!(12.0, 4.0, 0.0));
+    /// ```
+    #[inline]
+    pub fn grad<Tags>(&self, _: Tags) -> GradOut<Self, Tags>
+    where
+        Self: Grad<Tags>,
+    {
+        Grad::grad(self)
+    }
+
+    /// Second derivatives with respect to each pair of tags in a paren of tags, with one row per
+    /// tag.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::{var::var, Expression};
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    ///
+    /// let f = x * x * y;
+    /// assert_eq!(f.hessian(paren!(X, Y)).eval(), paren!(
+    ///     paren!(6.0, 4.0),
+    ///     paren!(4.0, 0.0),
+    /// ));
+    ///
+    /// // values can also be converted into arrays
+    /// use typing::paren::{array::IntoArrayFn, Paren};
+    /// let h = f.hessian(paren!(X, Y)).eval().map::<IntoArrayFn>().into_array();
+    /// assert_eq!(h, [[6.0, 4.0], [4.0, 0.0]]);
+    /// ```
+    #[inline]
+    pub fn hessian<Tags>(&self, _: Tags) -> HessianOut<Self, Tags>
+    where
+        Self: Grad<Tags>,
+        GradOut<Self, Tags>: Jacobian<Tags>,
+    {
+        Grad::<Tags>::grad(self).jacobian()
+    }
+
     #[inline]
     pub fn reduce(self) -> Expr<Reduced<T>>
     where
@@ -55,6 +289,47 @@ impl<T> Expr<T> {
     {
         Expr(self.0.reduce())
     }
+
+    /// Canonicalise the whole expression, see [`Simplify`](simplify/trait.Simplify.html).
+    /// # Examples
+    /// ```
+    /// use typing::expr::{var::var, Expr, One};
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(3.0, X);
+    /// let y = var(2.0, Y);
+    ///
+    /// let f = (x * y / (x * -y)).simplify();
+    /// assert_eq!(f.eval(), -1.0);
+    ///
+    /// // the derivative of x * x reduces to x + x, which is collected into 2 * x
+    /// let df = (x * x).deriv(X).simplify();
+    /// assert_eq!(format!("{:?}", df), "Expr(Mul(Const(2.0), X(3.0)))");
+    ///
+    /// let g: Expr<One<f64>> = (x / x).simplify();
+    /// ```
+    #[inline]
+    pub fn simplify(self) -> Expr<Simplified<T>>
+    where
+        T: Simplify,
+    {
+        Expr(self.0.simplify())
+    }
+}
+
+// parens of expressions evaluate to parens of their values
+impl<A: Expression, B: Expression> Expression for (A, B) {
+    type Output = (A::Output, B::Output);
+    #[inline]
+    fn eval(self) -> Self::Output {
+        (self.0.eval(), self.1.eval())
+    }
+}
+
+impl Expression for () {
+    type Output = ();
+    #[inline(always)]
+    fn eval(self) -> Self::Output {}
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -68,7 +343,11 @@ pub type Reduced<T> = <T as Reduce>::Output;
 
 #[cfg(test)]
 mod tests {
-    use crate::expr::var::var;
+    use crate::expr::constant::{constant, one, zero, Const};
+    use crate::expr::ops::{Mul, Neg, Sub};
+    use crate::expr::var::{placeholder, var, Var};
+    use crate::expr::{Expr, One, Zero};
+    use crate::paren;
     use crate::tags::{X, Y, Z};
 
     #[test]
@@ -86,4 +365,757 @@ mod tests {
         let w_dz = w.deriv(Z);
         assert_eq!(w_dz.eval(), -24.0);
     }
+
+    #[test]
+    fn constants() {
+        let x = var(3.0, X);
+        let y = var(2.0, Y);
+
+        // note: literals on the left hand side may need a suffix to be inferred
+        let w = 2.0f64 * x * x + y / 4.0 - 1.0;
+        assert_eq!(w.eval(), 17.5);
+
+        let w_dx = w.deriv(X);
+        assert_eq!(w_dx.eval(), 12.0);
+
+        let w_dy = (y * 0.25 + x).deriv(Y);
+        assert_eq!(w_dy.eval(), 0.25);
+
+        let c = constant(5) * var(2, Z) % 3;
+        assert_eq!(c.eval(), 1);
+
+        let r: Expr<Var<f64, X>> = (x * one::<f64>() + zero::<f64>()).reduce();
+        assert_eq!(r.eval(), 3.0);
+    }
+
+    #[test]
+    fn identities() {
+        let x = var(3.0, X);
+        let n = var(5i32, Y);
+
+        assert_eq!(x.deriv(X).eval(), 1.0);
+        assert_eq!(x.deriv(Y).eval(), 0.0);
+        assert_eq!(n.deriv(Y).eval(), 1i32);
+        assert_eq!(n.deriv(X).deriv(Y).eval(), 0i32);
+
+        assert_eq!((x + one::<f64>()).eval(), 4.0);
+        assert_eq!((n - one::<i32>() - one::<i32>()).eval(), 3);
+        assert_eq!((x / 4.0).deriv(X).eval(), 0.25);
+
+        // derivatives of variables don't need anything of their values
+        struct Opaque;
+        let o = var(Opaque, X);
+        let _: Expr<One<Opaque>> = o.deriv(X);
+        let _: Expr<Zero<Opaque>> = o.deriv(Y);
+    }
+
+    #[test]
+    fn elementary() {
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        let x = var(0.5, X);
+        let y = var(2.0, Y);
+
+        let w = x.sin() * y.exp() + (x * y).ln() - y.sqrt().tanh();
+        let expected = 0.5f64.sin() * 2.0f64.exp() + 1.0f64.ln() - 2.0f64.sqrt().tanh();
+        assert!(close(w.eval(), expected));
+
+        let w_dx = w.deriv(X);
+        assert!(close(w_dx.eval(), 0.5f64.cos() * 2.0f64.exp() + 2.0));
+
+        let t = 2.0f64.sqrt().tanh();
+        let w_dy = w.deriv(Y);
+        let expected = 0.5f64.sin() * 2.0f64.exp() + 0.5 - (1.0 - t * t) / (2.0 * 2.0f64.sqrt());
+        assert!(close(w_dy.eval(), expected));
+
+        let c = x.cos().deriv(X);
+        assert!(close(c.eval(), -(0.5f64.sin())));
+
+        let p = x.powi(3) * y.powf(x);
+        assert!(close(p.eval(), 0.125 * 2.0f64.powf(0.5)));
+        let expected = 3.0 * 0.25 * 2.0f64.powf(0.5) + 0.125 * 2.0f64.powf(0.5) * 2.0f64.ln();
+        assert!(close(p.deriv(X).eval(), expected));
+        assert!(close(p.deriv(Y).eval(), 0.125 * 0.5 * 2.0f64.powf(-0.5)));
+
+        let e = (zero::<f64>().exp() * x).reduce();
+        assert_eq!(e.eval(), 0.5);
+        let q = y.powf(constant(3.0)).deriv(Y);
+        assert!(close(q.eval(), 12.0));
+    }
+
+    #[test]
+    fn powi_min_deriv() {
+        // the exponent of the derivative of x^i32::MIN isn't representable as an i32
+        let f = var(1.0, X).powi(i32::MIN).deriv(X);
+        assert_eq!(f.eval(), i32::MIN as f64);
+        assert_eq!(f.to_string(), "-2147483648 * X^(-2147483649)");
+    }
+
+    #[test]
+    fn pow_zero_deriv() {
+        use crate::int::Z0;
+        use crate::paren::ops::Lookup;
+
+        // the derivative of x^0 is zero even at zero
+        let x = var(0.0, X);
+        assert_eq!(x.powi(0).deriv(X).eval(), 0.0);
+        assert_eq!(x.pow::<Z0>().deriv(X).eval(), 0.0);
+        assert_eq!((x + x.powi(0)).eval_dual_grad(paren!(X)).deriv.0, 1.0);
+        assert_eq!((x + x.pow::<Z0>()).eval_dual_grad(paren!(X)).deriv.0, 1.0);
+        let (_, grad) = (x + x.powi(0)).grad_reverse(paren!(X));
+        assert_eq!(*Lookup::<X>::lookup(&grad), 1.0);
+        let (_, grad) = (x + x.pow::<Z0>()).grad_reverse(paren!(X));
+        assert_eq!(*Lookup::<X>::lookup(&grad), 1.0);
+    }
+
+    #[test]
+    fn eval_with() {
+        let x = placeholder::<f64, _>(X);
+        let y = placeholder::<f64, _>(Y);
+
+        // placeholders and plain variables can be mixed
+        let w = (x * y + var(1.0, Z)).sin() + x * x;
+        let w_dx = w.deriv(X);
+
+        for &(a, b) in &[(0.0, 1.0), (1.0, 2.0), (-2.0, 0.5)] {
+            let env = paren!((Y, b), (X, a));
+            assert_eq!(w.eval_with(&env), (a * b + 1.0).sin() + a * a);
+            assert_eq!(w_dx.eval_with(&env), (a * b + 1.0).cos() * b + 2.0 * a);
+        }
+
+        // powers of placeholders can be differentiated as well
+        let env = paren!((X, 3.0));
+        assert_eq!(x.powi(2).deriv(X).eval_with(&env), 6.0);
+        assert_eq!(x.pow::<crate::int::P2>().deriv(X).eval_with(&env), 6.0);
+    }
+
+    #[test]
+    #[allow(clippy::type_complexity)]
+    fn simplify() {
+        let x = var(3.0, X);
+        let y = var(2.0, Y);
+
+        let _: Expr<Var<f64, X>> = (-(-x)).simplify();
+        let _: Expr<Zero<f64>> = (x - x).simplify();
+        let _: Expr<One<f64>> = (x / x).simplify();
+        let _: Expr<Sub<Var<f64, X>, Var<f64, Y>>> = (x + -y).simplify();
+        let _: Expr<Neg<Mul<Var<f64, X>, Var<f64, Y>>>> = (-x * y).simplify();
+
+        let w: Expr<Mul<Const<f64>, Var<f64, X>>> = (x + x).simplify();
+        assert_eq!(w.eval(), 6.0);
+        let w: Expr<Mul<Const<f64>, Var<f64, X>>> = (x * 2.0 * 3.0 - x).simplify();
+        assert_eq!((w.0).0, Const(5.0));
+        let w: Expr<Const<f64>> = (x * 4.0 / (x * 2.0)).simplify();
+        assert_eq!(w.eval(), 2.0);
+        let w: Expr<Const<i32>> = (constant(2) * constant(3) - one::<i32>()).simplify();
+        assert_eq!(w.eval(), 5);
+
+        let f = (x * y - y * x + x * 2.0).sin();
+        let df = f.deriv(X).simplify();
+        assert_eq!(df.eval(), 6.0f64.cos() * 2.0);
+        let _: Expr<Zero<f64>> = (x * y - x * y).deriv(Y).simplify();
+
+        // operators without rules of their own still simplify their operands
+        let n = var(7, X);
+        let m = var(4, Y);
+        let w = (((n + n) % -(-m)) ^ !((m * one::<i32>()) << constant(2))).simplify();
+        assert_eq!(w.to_string(), "2 * X % Y xor !(Y << 2)");
+        assert_eq!(w.eval(), (14 % 4) ^ !(4 << 2));
+    }
+
+    #[test]
+    fn print() {
+        let x = var(1.0, X);
+        let y = var(2.0, Y);
+        let z = placeholder::<f64, _>(Z);
+
+        assert_eq!((x - (y - z)).to_string(), "X - (Y - Z)");
+        assert_eq!((x - y - z).to_string(), "X - Y - Z");
+        assert_eq!((x + (y - z)).to_string(), "X + Y - Z");
+        assert_eq!(((x + y) * z).to_string(), "(X + Y) * Z");
+        assert_eq!((x * y % z).to_string(), "X * Y % Z");
+        assert_eq!((x * (y % z)).to_string(), "X * (Y % Z)");
+        assert_eq!((-(-x)).to_string(), "-(-X)");
+        assert_eq!((-x.powi(2)).to_string(), "-X^2");
+        assert_eq!((-x).powi(-2).to_string(), "(-X)^(-2)");
+        assert_eq!((x.powf(y.powf(z))).to_string(), "X^Y^Z");
+        assert_eq!((x.powf(y).powf(z)).to_string(), "(X^Y)^Z");
+        assert_eq!((x / y).deriv(Y).to_string(), "-(X / Y^2)");
+        assert_eq!((x * -1.0).to_string(), "X * -1");
+        assert_eq!(constant(-1.0).powi(2).to_string(), "(-1)^2");
+        assert_eq!((x / y).exp().sqrt().to_string(), "sqrt(exp(X / Y))");
+
+        assert_eq!(
+            (x / y).exp().powi(2).to_latex(),
+            r"\left(e^{\frac{X}{Y}}\right)^{2}"
+        );
+        assert_eq!(
+            (x / y).powf(z - x).to_latex(),
+            r"\left(\frac{X}{Y}\right)^{Z - X}"
+        );
+        assert_eq!(
+            (x + y).sqrt().ln().to_latex(),
+            r"\ln\left(\sqrt{X + Y}\right)"
+        );
+
+        let f = (x * y).sin() / y;
+        assert_eq!(f.deriv(X).to_sexpr(), "(/ (* (cos (* X Y)) Y) Y)");
+        assert_eq!(f.deriv(X).to_string(), format!("{}", (x * y).cos() * y / y));
+    }
+
+    #[test]
+    fn dynamic() {
+        use crate::expr::cond::select;
+        use crate::expr::dynamic::{DynExpr, ParseError, ToDyn, UnboundVariable};
+        use std::collections::HashMap;
+
+        let parse = |s: &str| s.parse::<DynExpr>();
+        let vars: HashMap<String, f64> = vec![("x".to_string(), 2.0), ("y".to_string(), 0.5)]
+            .into_iter()
+            .collect();
+
+        for s in &[
+            "x - (y - 1)",
+            "-x^2",
+            "(-x)^(-2)",
+            "x^y^2",
+            "25 % x * y",
+            "sqrt(exp(x / y))",
+            "select(x <= y, x * y, -x)",
+            "x != (y == 1)",
+            "{ let t = x * y; t * t + t } / { let x = y; x }",
+        ] {
+            assert_eq!(parse(s).unwrap().to_string(), *s);
+        }
+        assert_eq!(parse("1 - 2 - 3").unwrap().eval(&vars), Ok(-4.0));
+        assert_eq!(parse("2.5e1 % x").unwrap().eval(&vars), Ok(1.0));
+        assert_eq!(parse("-x^2").unwrap().eval(&vars), Ok(-4.0));
+        assert_eq!(
+            parse("2^-1").unwrap(),
+            DynExpr::Powi(Box::new(DynExpr::Const(2.0)), -1)
+        );
+        assert_eq!(parse(" tanh( x*y ) ").unwrap().to_sexpr(), "(tanh (* x y))");
+        let f = parse("select(x > y, x * x, y)").unwrap();
+        assert_eq!(f.eval(&vars), Ok(4.0));
+        assert_eq!(f.deriv("x").unwrap().eval(&vars), Ok(4.0));
+        assert_eq!(f.deriv("y").unwrap().eval(&vars), Ok(0.0));
+        assert_eq!(f.deriv("z"), Ok(DynExpr::Const(0.0)));
+        assert_eq!(parse("(x >= 2) + (x < y)").unwrap().eval(&vars), Ok(1.0));
+        // bindings shadow variables of the same name, also in their derivatives
+        let f = parse("{ let x = x * y; x * x + y }").unwrap();
+        assert_eq!(f.eval(&vars), Ok(1.5));
+        assert_eq!(f.deriv("x").unwrap().eval(&vars), Ok(1.0));
+        assert_eq!(f.deriv("y").unwrap().eval(&vars), Ok(5.0));
+        assert_eq!(
+            f.deriv("x").unwrap().to_string(),
+            "{ let x = x * y; (x + x) * y }"
+        );
+        let f = parse("{ let x = x * x; x * x }").unwrap();
+        assert_eq!(f.deriv("x").unwrap().eval(&vars), Ok(32.0));
+        assert_eq!(
+            f.deriv("x").unwrap().to_string(),
+            "{ let x = x * x; x + x } * (x + x)"
+        );
+        let f = parse("{ let t = x * y; t * t + x }").unwrap();
+        assert_eq!(
+            f.deriv("x").unwrap().to_string(),
+            "{ let t = x * y; 1 + (t + t) * y }"
+        );
+        assert_eq!(
+            parse("{ let t = y; x }")
+                .unwrap()
+                .deriv("x")
+                .unwrap()
+                .to_string(),
+            "1"
+        );
+        assert_eq!(
+            parse("x + z").unwrap().eval(&vars),
+            Err(UnboundVariable("z".to_string()))
+        );
+
+        let error = |position, message: &str| {
+            Err(ParseError {
+                position,
+                message: message.to_string(),
+            })
+        };
+        assert_eq!(parse("x +"), error(3, "unexpected end of input"));
+        assert_eq!(parse("(x + y"), error(6, "expected `)`"));
+        assert_eq!(parse("x y"), error(2, "unexpected `y`"));
+        assert_eq!(parse("cot(x)"), error(0, "unknown function `cot`"));
+        assert_eq!(parse("1.2.3"), error(0, "invalid number `1.2.3`"));
+        assert_eq!(parse("x < y < 1"), error(6, "unexpected `<`"));
+        assert_eq!(parse("{ x }"), error(3, "expected `let`"));
+        assert_eq!(parse("{ let 1 = x; x }"), error(6, "expected a name"));
+        assert_eq!(parse("{ let t = x }"), error(12, "expected `;`"));
+        assert_eq!(
+            parse("select(x, y)"),
+            error(0, "wrong number of arguments to `select`")
+        );
+        let nested = format!("{}x{}", "(".repeat(200_000), ")".repeat(200_000));
+        assert_eq!(parse(&nested), error(256, "expression nested too deeply"));
+        assert_eq!(
+            parse(&"-".repeat(300)),
+            error(256, "expression nested too deeply")
+        );
+        assert!(parse(&format!("{}x{}", "(".repeat(255), ")".repeat(255))).is_ok());
+        // and so are the operations, which are as deep as a chain of them is long
+        let sum = vec!["x"; 100_000].join(" + ");
+        assert_eq!(parse(&sum), error(1022, "expression nested too deeply"));
+        // expressions as deep as that can be evaluated and differentiated on the stack of a test
+        let squares = parse(&vec!["x * x"; 255].join(" + ")).unwrap();
+        assert_eq!(squares.deriv("x").unwrap().eval(&vars), Ok(1020.0));
+        let power = format!("{}x{}", "sin(".repeat(200), "^2)".repeat(200));
+        assert_eq!(parse(&power), error(288, "expression nested too deeply"));
+
+        // the exponent of the derivative of x^i64::MIN isn't representable as an i64
+        let f = parse("x^-9223372036854775808").unwrap().deriv("x").unwrap();
+        assert_eq!(
+            f.to_string(),
+            "-9223372036854776000 * (x^(-9223372036854775808) / x)"
+        );
+
+        // derivatives of the bridged static expression agree with the static ones
+        let x = var(2.0, X);
+        let y = var(0.5, Y);
+        let f = (x * y).sin() / x.powf(y) + (x - y).powi(3).ln();
+        let vars: HashMap<&str, f64> = vec![("X", 2.0), ("Y", 0.5)].into_iter().collect();
+        let g = f.to_dyn();
+        assert_eq!(g.eval(&vars), Ok(f.eval()));
+        assert_eq!(g.deriv("X").unwrap().eval(&vars), Ok(f.deriv(X).eval()));
+        assert_eq!(g.deriv("Y").unwrap().eval(&vars), Ok(f.deriv(Y).eval()));
+
+        let f = select(x.gt(y), x * y, x.min(y));
+        let g = f.to_dyn();
+        assert_eq!(g.to_string(), "select(X > Y, X * Y, select(X < Y, X, Y))");
+        assert_eq!(g.eval(&vars), Ok(f.eval()));
+        assert_eq!(g.deriv("X").unwrap().eval(&vars), Ok(f.deriv(X).eval()));
+    }
+
+    #[test]
+    fn dual() {
+        use crate::expr::dual::Dual;
+        use crate::expr::Expression;
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        let x = var(0.7, X);
+        let y = var(1.3, Y);
+        let z = var(-0.4, Z);
+        let f = (x * y + z).tanh() * x.powf(y) - (y / (z * z)).sqrt() + (x - z).exp().powi(3);
+
+        let d = f.eval_dual_grad(paren!(X, Y, Z));
+        let g = f.grad(paren!(X, Y, Z)).eval();
+        assert!(close(d.value, f.eval()));
+        assert!(close((d.deriv).0, g.0));
+        assert!(close((d.deriv).1 .0, (g.1).0));
+        assert!(close((d.deriv).1 .1 .0, ((g.1).1).0));
+
+        let v = f.eval_dual(paren!((Z, 2.0), (X, -1.0)));
+        assert!(close(v.deriv, 2.0 * ((g.1).1).0 - g.0));
+
+        // the logarithm of the base isn't needed for a constant exponent
+        let p = |a: f64| var(a, X).powf(constant(2.0)).eval_dual_grad(paren!(X));
+        assert_eq!(p(-1.0).deriv.0, -2.0);
+        assert_eq!(p(0.0).deriv.0, 0.0);
+        let q = var(1.0, X).powi(i32::MIN).eval_dual_grad(paren!(X));
+        assert_eq!(q.deriv.0, i32::MIN as f64);
+        let v = (x + constant(0.0).powi(0)).eval_dual(paren!((X, 1.0)));
+        assert_eq!(v.deriv, 1.0);
+
+        // dual numbers can be the values of variables as well, e.g. for second derivatives of
+        // x / (x + x^2) = 1 / (1 + x)
+        let d = var(Dual::new(2.0, 1.0), X);
+        let q = (d / (d + d * d)).deriv(X).eval();
+        assert!(close(q.value, -1.0 / 9.0));
+        assert!(close(q.deriv, 2.0 / 27.0));
+        let q = (d / (d + d * d)).deriv(X).deriv(X).eval();
+        assert!(close(q.value, 2.0 / 27.0));
+    }
+
+    #[test]
+    fn tape() {
+        use crate::expr::Expression;
+        use crate::paren::ops::Lookup;
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        let x = var(0.7, X);
+        let y = var(1.3, Y);
+        let z = var(-0.4, Z);
+        let f = (x * y + z).tanh() * x.powf(y) - (y / (z * z)).sqrt() + (x - z).exp().powi(3);
+
+        let (value, grad) = f.grad_reverse(paren!(X, Y, Z));
+        let g = f.grad(paren!(X, Y, Z)).eval();
+        assert!(close(value, f.eval()));
+        assert!(close(*Lookup::<X>::lookup(&grad), g.0));
+        assert!(close(*Lookup::<Y>::lookup(&grad), (g.1).0));
+        assert!(close(*Lookup::<Z>::lookup(&grad), ((g.1).1).0));
+
+        let (_, partial) = f.grad_reverse(paren!(Y));
+        assert!(close(*Lookup::<Y>::lookup(&partial), (g.1).0));
+
+        let (value, grad) = (-(x * y % z)).grad_reverse(paren!(X, Y));
+        assert!(close(value, -(0.91 % -0.4)));
+        assert!(close(*Lookup::<X>::lookup(&grad), -1.3));
+        assert!(close(*Lookup::<Y>::lookup(&grad), -0.7));
+
+        // an infinite partial with respect to y doesn't leak into the derivative of x
+        let y = var(0.0, Y);
+        let (_, grad) = (x + y.sqrt()).grad_reverse(paren!(X, Y));
+        assert_eq!(*Lookup::<X>::lookup(&grad), 1.0);
+        assert_eq!(*Lookup::<Y>::lookup(&grad), f64::INFINITY);
+        let (_, grad) = (x + (y * 2.0).sqrt()).grad_reverse(paren!(X, Y));
+        assert_eq!(*Lookup::<X>::lookup(&grad), 1.0);
+
+        let (_, grad) = var(1.0, X).powi(i32::MIN).grad_reverse(paren!(X));
+        assert_eq!(*Lookup::<X>::lookup(&grad), i32::MIN as f64);
+    }
+
+    #[test]
+    fn matrix() {
+        use crate::expr::matrix::{column, matrix, Matrix, Vector};
+
+        let rotate = matrix([[0.0, -1.0], [1.0, 0.0]]);
+        let x = column([3.0, 4.0]);
+
+        let y: Vector<f64, 2> = (rotate * rotate * x).eval();
+        assert_eq!(y, Matrix([[-3.0], [-4.0]]));
+        assert_eq!(y[(1, 0)], -4.0);
+
+        // the factors of a chain are evaluated one product at a time
+        let r = rotate * rotate * rotate * rotate;
+        let z = (r * r * r * r * r * r * r * r * x).eval();
+        assert_eq!(z, x.eval());
+
+        let norm = (x.transpose() * x).eval();
+        assert_eq!(norm, Matrix([[25.0]]));
+
+        let outer = (0.5f64 * (x * x.transpose()) + -rotate).eval();
+        assert_eq!(outer, Matrix([[4.5, 7.0], [5.0, 8.0]]));
+    }
+
+    #[test]
+    fn pow() {
+        use crate::expr::Expression;
+        use crate::int::{N3, P4};
+
+        let x = var(1.5, X);
+        let y = var(-2.0, Y);
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        let f = x.pow::<P4>() * y + (x / y).pow::<N3>();
+        let g = x * x * x * x * y + y * y * y / (x * x * x);
+
+        assert!(close(f.eval(), g.eval()));
+        let df = f.grad(paren!(X, Y)).eval();
+        let dg = g.grad(paren!(X, Y)).eval();
+        assert!(close(df.0, dg.0));
+        assert!(close((df.1).0, (dg.1).0));
+
+        let (_, grad) = f.grad_reverse(paren!(X, Y));
+        assert!(close((grad.0).1, df.0));
+        assert!(close(f.eval_dual_grad(paren!(X, Y)).deriv.0, df.0));
+    }
+
+    #[test]
+    fn cond() {
+        use crate::expr::cond::select;
+        use crate::expr::constant::constant;
+        use crate::expr::var::placeholder;
+        use crate::expr::vector::{vector, LengthMismatch};
+        use crate::paren::Paren;
+
+        // clamp to [0, 1]
+        let x = placeholder::<f64, _>(X);
+        let clamp = x.max(Expr(Zero::<f64>::new())).min(Expr(One::<f64>::new()));
+        let dclamp = clamp.deriv(X);
+        for &(v, value, deriv) in &[(-1.0, 0.0, 0.0), (0.5, 0.5, 1.0), (2.0, 1.0, 0.0)] {
+            let env = paren!((X, v));
+            assert_eq!(clamp.eval_with(&env), value);
+            assert_eq!(dclamp.eval_with(&env), deriv);
+        }
+
+        let y = var(2.0, Y);
+        let f = select(y.ge(var(1.0, Z)), y * y, -y);
+        assert_eq!(f.to_string(), "select(Y >= Z, Y * Y, -Y)");
+        assert_eq!(f.to_sexpr(), "(select (>= Y Z) (* Y Y) (- Y))");
+        assert_eq!(
+            f.to_latex(),
+            r"\begin{cases} Y \cdot Y & Y \ge Z \\ -Y & \text{otherwise} \end{cases}"
+        );
+        assert_eq!((y + y).lt(y).to_string(), "Y + Y < Y");
+        assert_eq!(y.eq(y.lt(y)).to_string(), "Y == (Y < Y)");
+        let z = var(1.0, Z);
+        assert_eq!(y.lt(z).to_latex(), "Y < Z");
+        assert_eq!(y.le(z).to_latex(), r"Y \le Z");
+        assert_eq!(y.gt(z).to_latex(), "Y > Z");
+        assert_eq!(y.ge(z).to_latex(), r"Y \ge Z");
+        assert_eq!(y.eq(z).to_latex(), "Y = Z");
+        assert_eq!(y.ne(z).to_latex(), r"Y \ne Z");
+        assert_eq!(
+            select((y + y).le(-(-z)), y * 2.0 * 3.0, y - y)
+                .simplify()
+                .to_string(),
+            "select(2 * Y <= Z, 6 * Y, 0)"
+        );
+
+        let _: crate::Paren!(Y, Z) = f.free_vars();
+        assert_eq!(f.deriv(Y).eval(), 4.0);
+        assert_eq!(f.eval_dual_grad(paren!(Y, Z)).deriv, paren!(4.0, 0.0));
+        let (value, grad) = f.grad_reverse(paren!(Y, Z));
+        assert_eq!((value, *grad.lookup(Y), *grad.lookup(Z)), (4.0, 4.0, 0.0));
+
+        // equal values with different derivatives still compare equal
+        let (a, b) = (var(1.0, Y), var(1.0, Z));
+        let g = select(a.ge(b), a * 3.0, b);
+        assert_eq!(g.eval_dual_grad(paren!(Y, Z)).deriv, paren!(3.0, 0.0));
+        let (_, grad) = g.grad_reverse(paren!(Y, Z));
+        assert_eq!((*grad.lookup(Y), *grad.lookup(Z)), (3.0, 0.0));
+
+        let v = [-2.0, 0.5, 3.0];
+        let w = [1.0, 1.0, 1.0];
+        assert_eq!(vector(&v).abs().eval_vec().unwrap(), [2.0, 0.5, 3.0]);
+        assert_eq!(
+            select(vector(&v).gt(vector(&w)), vector(&v), constant(0.0))
+                .eval_vec()
+                .unwrap(),
+            [0.0, 0.0, 3.0]
+        );
+        assert_eq!(
+            select(
+                vector(&v).lt(constant(0.0)),
+                vector(&w[1..]),
+                vector(&w[1..])
+            )
+            .eval_vec(),
+            Err(LengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn call() {
+        use crate::expr::call::Differentiable;
+        use crate::expr::dynamic::{DynDifferentiable, DynExpr, ToDyn, UnknownDeriv};
+        use crate::expr::vector::vector;
+        use crate::expr::Expression;
+        use crate::impl_func;
+        use std::collections::HashMap;
+
+        #[derive(Copy, Clone, Debug, Default)]
+        struct Cube;
+        #[derive(Copy, Clone, Debug, Default)]
+        struct Square3;
+        #[derive(Copy, Clone, Debug, Default)]
+        struct Times6;
+        impl_func!(for Cube { |x: f64| -> f64 { x * x * x } });
+        impl_func!(for Square3 { |x: f64| -> f64 { 3.0 * x * x } });
+        impl_func!(for Times6 { |x: f64| -> f64 { 6.0 * x } });
+        impl Differentiable for Cube {
+            type Deriv = Square3;
+        }
+        impl Differentiable for Square3 {
+            type Deriv = Times6;
+        }
+        impl DynDifferentiable<f64> for Times6 {}
+
+        let x = var(2.0, X);
+        let y = var(3.0, Y);
+        let f = (x * y).apply::<Cube>() + x;
+        assert_eq!(f.eval(), 218.0);
+        let grad = f.grad(paren!(X, Y)).eval();
+        assert_eq!(grad, paren!(325.0, 216.0));
+        assert_eq!(f.eval_dual_grad(paren!(X, Y)).deriv, grad);
+        assert_eq!(f.grad_reverse(paren!(X, Y)).0, 218.0);
+        assert_eq!(((f.grad_reverse(paren!(X, Y)).1).0).1, 325.0);
+
+        assert_eq!(f.to_sexpr(), "(+ (Cube (* X Y)) X)");
+        assert_eq!(
+            f.to_latex(),
+            r"\operatorname{Cube}\left(X \cdot Y\right) + X"
+        );
+        let _: crate::Paren!(X, Y) = f.free_vars();
+        assert_eq!(f.subst(Y, Expr(Const(1.0))).eval(), 10.0);
+        assert_eq!(
+            (x * 2.0 * 3.0).apply::<Cube>().simplify().to_string(),
+            "Cube(6 * X)"
+        );
+
+        let v = [1.0, 2.0];
+        assert_eq!(vector(&v).apply::<Cube>().eval_vec(), Ok(vec![1.0, 8.0]));
+
+        let g = f.to_dyn();
+        let vars: HashMap<&str, f64> = vec![("X", 2.0), ("Y", 3.0)].into_iter().collect();
+        assert_eq!(g.to_string(), "Cube(X * Y) + X");
+        assert_eq!(g.to_latex(), f.to_latex());
+        assert_eq!(g.eval(&vars), Ok(218.0));
+        assert_eq!(g.deriv("X").unwrap().eval(&vars), Ok(325.0));
+        assert_eq!(g.deriv("Y").unwrap().eval(&vars), Ok(216.0));
+        // the whole chain of derivatives is kept
+        let gxx = g.deriv("X").unwrap().deriv("X").unwrap();
+        assert_eq!(gxx.eval(&vars), Ok(f.deriv(X).deriv(X).eval()));
+        // the derivative of Times6 isn't known, but it isn't needed for a constant argument
+        assert_eq!(gxx.deriv("X"), Err(UnknownDeriv("Times6".to_string())));
+        assert_eq!(gxx.deriv("Z"), Ok(DynExpr::Const(0.0)));
+    }
+
+    #[test]
+    fn dyn_func_unknown_deriv() {
+        use crate::expr::dynamic::{DynExpr, DynFunc, UnknownDeriv};
+        use crate::impl_func;
+
+        #[derive(Copy, Clone, Debug, Default)]
+        struct Sqrt3;
+        impl_func!(for Sqrt3 { |x: f64| -> f64 { x.cbrt() } });
+
+        let x = Box::new(DynExpr::<f64>::Var("x".to_string()));
+        let f = DynExpr::Call(Box::new(DynFunc::new::<Sqrt3>()), x);
+        assert_eq!(f.deriv("x"), Err(UnknownDeriv("Sqrt3".to_string())));
+        assert_eq!(
+            f.deriv("x").unwrap_err().to_string(),
+            "the derivative of `Sqrt3` isn't known"
+        );
+        assert_eq!(f.deriv("y"), Ok(DynExpr::Const(0.0)));
+    }
+
+    #[test]
+    fn share() {
+        use crate::expr::dynamic::ToDyn;
+        use crate::expr::var::placeholder;
+        use crate::expr::vector::vector;
+        use crate::paren::Paren;
+        use crate::tags::{S, T};
+        use std::collections::HashMap;
+
+        fn close(a: f64, b: f64) -> bool {
+            (a - b).abs() < 1e-12
+        }
+
+        // placeholders in the value and the body, evaluated in a chained environment
+        let x = placeholder::<f64, _>(X);
+        let y = placeholder::<f64, _>(Y);
+        let f = (x * y).sin().let_in(T, |t| t * t + t * y);
+        let g = (x * y).sin() * (x * y).sin() + (x * y).sin() * y;
+        let env = paren!((X, 0.5), (Y, 3.0));
+        assert_eq!(f.eval_with(&env), g.eval_with(&env));
+        assert!(close(
+            f.deriv(X).eval_with(&env),
+            g.deriv(X).eval_with(&env)
+        ));
+        assert!(close(
+            f.deriv(Y).eval_with(&env),
+            g.deriv(Y).eval_with(&env)
+        ));
+        let _: crate::Paren!(X, Y) = f.free_vars();
+
+        // free placeholders are seeded from the environment in forward and reverse mode
+        let (dx, dy) = (f.deriv(X).eval_with(&env), f.deriv(Y).eval_with(&env));
+        let d = f.eval_dual_with(&env, paren!((X, 1.0)));
+        assert_eq!(d.value, f.eval_with(&env));
+        assert!(close(d.deriv, dx));
+        let (value, grad) = f.grad_reverse_with(&env, paren!(X, Y));
+        assert_eq!(value, f.eval_with(&env));
+        assert!(close(*grad.lookup(X), dx) && close(*grad.lookup(Y), dy));
+        assert_eq!((x * x).eval_dual_with(&env, paren!((X, 1.0))).deriv, 1.0);
+        let (_, grad) = (x * x).grad_reverse_with(&env, paren!(X));
+        assert_eq!(*grad.lookup(X), 1.0);
+
+        // the binding shadows substitutions of its tag, and unused ones are reduced away
+        let h = x.let_in(T, |t| t + y);
+        assert_eq!(h.subst(T, var(1.0, Z)).to_string(), "{ let T = X; T + Y }");
+        assert_eq!(h.subst(X, var(1.0, Z)).to_string(), "{ let T = Z; T + Y }");
+        assert_eq!(h.deriv(Y).to_string(), "1");
+        assert_eq!(x.let_in(T, |_| y).reduce().to_string(), "Y");
+        assert_eq!(h.to_latex(), r"\left. T + Y \right|_{T = X}");
+        assert_eq!(h.to_sexpr(), "(let (T X) (+ T Y))");
+
+        // nested bindings and the elimination of subexpressions with placeholders
+        let k = (x * y).sin() + (x * y).cos() + (x * y).sin();
+        let shared = k.cse();
+        assert_eq!(
+            shared.to_string(),
+            "{ let t0 = X * Y; { let t1 = sin(t0); t1 + cos(t0) + t1 } }"
+        );
+        assert_eq!(shared.eval_with(&env), k.eval_with(&env));
+        assert!(close(
+            shared.deriv(X).eval_with(&env),
+            k.deriv(X).eval_with(&env)
+        ));
+        assert!(close(
+            shared.deriv(X).deriv(Y).eval_with(&env),
+            k.deriv(X).deriv(Y).eval_with(&env)
+        ));
+
+        // constants and powi are never shared, nor is anything containing them, and neither are
+        // expressions without repetitions
+        let a = var(2.0, S);
+        let c = (a * 2.0) * (a * 2.0) + a.pow::<crate::int::P2>() * a.pow::<crate::int::P2>();
+        assert_eq!(
+            c.cse().to_string(),
+            "{ let t0 = S^2; S * 2 * (S * 2) + t0 * t0 }"
+        );
+        let b = var(3.0, T);
+        let p = (a.powi(2) + b) * (a.powi(2) + b);
+        assert_eq!(p.cse().to_string(), "(S^2 + T) * (S^2 + T)");
+        assert_eq!(c.cse().eval(), 32.0);
+        assert_eq!((a + a * a).cse().to_string(), "S + S * S");
+        assert_eq!(f.cse().simplify().eval_with(&env), f.eval_with(&env));
+
+        // nested bindings in forward and reverse mode agree with the symbolic derivatives
+        let x = var(0.5f64, X);
+        let y = var(3.0f64, Y);
+        let f = (x * y)
+            .sin()
+            .let_in(T, |t| (t + x).let_in(S, |s| s * t + s * y));
+        let (dx, dy) = (f.deriv(X).eval(), f.deriv(Y).eval());
+        let d = f.eval_dual_grad(paren!(X, Y));
+        assert_eq!(d.value, f.eval());
+        assert!(close(d.deriv.0, dx) && close(d.deriv.1 .0, dy));
+        let (value, grad) = f.grad_reverse(paren!(X, Y));
+        assert_eq!(value, f.eval());
+        assert!(close(*grad.lookup(X), dx) && close(*grad.lookup(Y), dy));
+        assert!(close(f.cse().eval_dual(paren!((Y, 1.0))).deriv, dy));
+
+        // and element-wise, where the bound value is computed once for each element
+        let (u, v) = ([1.0, 2.0, 3.0], [0.5, 0.5, 2.0]);
+        let e = (vector(&u) * vector(&v)).let_in(T, |t| t * t + t);
+        assert_eq!(e.eval_vec(), Ok(vec![0.75, 2.0, 42.0]));
+        let (p, q) = (placeholder::<f64, _>(X), placeholder::<f64, _>(Y));
+        let e = (vector(&u) * p).let_in(T, |t| t * t + q);
+        let env = paren!((X, 2.0), (Y, -1.0));
+        assert_eq!(e.eval_vec_with(&env), Ok(vec![3.0, 15.0, 35.0]));
+        let mut out = [0.0; 3];
+        e.eval_into_with(&env, &mut out).unwrap();
+        assert_eq!(out, [3.0, 15.0, 35.0]);
+
+        // and as dynamic expressions
+        let g = f.to_dyn();
+        assert_eq!(
+            g.to_string(),
+            "{ let T = sin(X * Y); { let S = T + X; S * T + S * Y } }"
+        );
+        let vars: HashMap<&str, f64> = vec![("X", 0.5), ("Y", 3.0)].into_iter().collect();
+        assert_eq!(g.eval(&vars), Ok(f.eval()));
+        assert!(close(g.deriv("X").unwrap().eval(&vars).unwrap(), dx));
+        assert!(close(g.deriv("Y").unwrap().eval(&vars).unwrap(), dy));
+
+        // the compile time of the elimination grows steeply with the size of the expression, see
+        // Expr::cse, so the first derivative is as large as this test goes
+        let q = (x * y).sin() / (x + y);
+        assert!(close(q.deriv(X).cse().eval(), q.deriv(X).eval()));
+        let (shared, plain) = (q.cse().deriv(X).deriv(Y), q.deriv(X).deriv(Y));
+        assert!(close(shared.eval(), plain.eval()));
+    }
 }
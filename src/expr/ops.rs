@@ -1,31 +1,15 @@
+use crate::expr::constant::{Const, Scalar};
 use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::elementary::Pow;
+use crate::expr::env::EvalWith;
+use crate::expr::subst::{Subst, SubstOut};
 use crate::expr::{Expr, Expression, One, Reduce, Reduced, Zero};
-
-macro_rules! reduce_simple {
-    ($({$($c:tt $(: $c0:ident $(+ $cN:ident)*)?),*})? $typ:ty => Self) => {
-        impl<$($($c $(: $c0 $(+ $cN)*)?,)*)?>  Reduce for $typ {
-            type Output = Self;
-            #[inline(always)]
-            fn reduce(self) -> Self::Output {
-                self
-            }
-        }
-    };
-    ($({$($c:tt $(: $c0:ident $(+ $cN:ident)*)?),*})? $typ:ty => $ret:tt) => {
-        impl<$($($c $(: $c0 $(+ $cN)*)?,)*)?>  Reduce for $typ {
-            type Output = $ret;
-            #[inline(always)]
-            fn reduce(self) -> Self::Output {
-                $ret
-            }
-        }
-    };
-}
+use crate::int::P2;
 
 macro_rules! expr_unop {
     ($name:ident, $method:ident) => {
         #[derive(Copy, Clone, Default, Debug)]
-        pub struct $name<T>(T);
+        pub struct $name<T>(pub T);
 
         impl<T> ::std::ops::$name for Expr<T> {
             type Output = Expr<$name<T>>;
@@ -48,6 +32,28 @@ macro_rules! expr_unop {
                 ::std::ops::$name::$method(self.0.eval())
             }
         }
+
+        impl<T, Env> EvalWith<Env> for $name<T>
+        where
+            T: EvalWith<Env>,
+            T::Output: ::std::ops::$name,
+        {
+            type Output = <T::Output as ::std::ops::$name>::Output;
+
+            #[inline(always)]
+            fn eval_with(&self, env: &Env) -> Self::Output {
+                ::std::ops::$name::$method(self.0.eval_with(env))
+            }
+        }
+
+        impl<T: Subst<Tag, R>, Tag, R> Subst<Tag, R> for $name<T> {
+            type Output = $name<SubstOut<T, Tag, R>>;
+
+            #[inline]
+            fn subst(self, replacement: &R) -> Self::Output {
+                $name(self.0.subst(replacement))
+            }
+        }
     };
 }
 
@@ -72,15 +78,15 @@ impl<T: Reduce> Reduce for Neg<T> {
     }
 }
 
-reduce_simple!(Neg<Zero> => Zero);
-reduce_simple!(Neg<One> => Self);
+reduce_simple!({T} Neg<Zero<T>> => Zero<T>);
+reduce_simple!({T} Neg<One<T>> => Self);
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
 
 macro_rules! expr_binop {
     ($name:ident, $method:ident) => {
         #[derive(Copy, Clone, Default, Debug)]
-        pub struct $name<L, R>(L, R);
+        pub struct $name<L, R>(pub L, pub R);
 
         impl<L, R> ::std::ops::$name<Expr<R>> for Expr<L> {
             type Output = Expr<$name<L, R>>;
@@ -104,6 +110,33 @@ macro_rules! expr_binop {
                 ::std::ops::$name::$method(self.0.eval(), self.1.eval())
             }
         }
+
+        impl<L, R, Env> EvalWith<Env> for $name<L, R>
+        where
+            L: EvalWith<Env>,
+            R: EvalWith<Env>,
+            L::Output: ::std::ops::$name<R::Output>,
+        {
+            type Output = <L::Output as ::std::ops::$name<R::Output>>::Output;
+
+            #[inline(always)]
+            fn eval_with(&self, env: &Env) -> Self::Output {
+                ::std::ops::$name::$method(self.0.eval_with(env), self.1.eval_with(env))
+            }
+        }
+
+        impl<L, R, Tag, X> Subst<Tag, X> for $name<L, R>
+        where
+            L: Subst<Tag, X>,
+            R: Subst<Tag, X>,
+        {
+            type Output = $name<SubstOut<L, Tag, X>, SubstOut<R, Tag, X>>;
+
+            #[inline]
+            fn subst(self, replacement: &X) -> Self::Output {
+                $name(self.0.subst(replacement), self.1.subst(replacement))
+            }
+        }
     };
 }
 
@@ -118,6 +151,46 @@ expr_binop!(Shl, shl);
 expr_binop!(Shr, shr);
 expr_binop!(Sub, sub);
 
+// binary operators between expressions and plain scalars, which are treated as constants. note
+// that the scalar types on the left hand side need to be listed explicitly due to orphan rules
+macro_rules! expr_scalar_binop {
+    ($name:ident, $method:ident; $($t:ty)*) => {
+        impl<L, T: Scalar> ::std::ops::$name<T> for Expr<L> {
+            type Output = Expr<$name<L, Const<T>>>;
+
+            #[inline]
+            fn $method(self, rhs: T) -> Self::Output {
+                Expr($name(self.0, Const(rhs)))
+            }
+        }
+
+        $(
+            impl<R> ::std::ops::$name<Expr<R>> for $t {
+                type Output = Expr<$name<Const<$t>, R>>;
+
+                #[inline]
+                fn $method(self, rhs: Expr<R>) -> Self::Output {
+                    Expr($name(Const(self), rhs.0))
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! expr_scalar_binops {
+    ($($name:ident, $method:ident;)*) => {$(
+        expr_scalar_binop!($name, $method; f32 f64 i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+    )*};
+}
+
+expr_scalar_binops!(
+    Add, add;
+    Div, div;
+    Mul, mul;
+    Rem, rem;
+    Sub, sub;
+);
+
 impl<Tag, L, R> Deriv<Tag> for Add<L, R>
 where
     L: Deriv<Tag>,
@@ -176,14 +249,14 @@ where
     L: Deriv<Tag> + Clone,
     R: Deriv<Tag> + Clone,
     Div<Dv<L, Tag>, R>: Reduce,
-    Mul<Div<L, Mul<R, R>>, Dv<R, Tag>>: Reduce,
-    Sub<Reduced<Div<Dv<L, Tag>, R>>, Reduced<Mul<Div<L, Mul<R, R>>, Dv<R, Tag>>>>: Reduce,
+    Mul<Div<L, Pow<R, P2>>, Dv<R, Tag>>: Reduce,
+    Sub<Reduced<Div<Dv<L, Tag>, R>>, Reduced<Mul<Div<L, Pow<R, P2>>, Dv<R, Tag>>>>: Reduce,
 {
     #[rustfmt::skip]
     #[allow(clippy::type_complexity)]
     type Output = Reduced<Sub<
         Reduced<Div<Dv<L, Tag>, R>>,
-        Reduced<Mul<Div<L, Mul<R, R>>, Dv<R, Tag>>>,
+        Reduced<Mul<Div<L, Pow<R, P2>>, Dv<R, Tag>>>,
     >>;
 
     #[inline]
@@ -191,7 +264,7 @@ where
         let Div(l, r) = self;
         Sub(
             Div(l.deriv(), r.clone()).reduce(),
-            Mul(Div(l.clone(), Mul(r.clone(), r.clone())), r.deriv()).reduce(),
+            Mul(Div(l.clone(), Pow(r.clone(), P2::default())), r.deriv()).reduce(),
         )
         .reduce()
     }
@@ -208,7 +281,7 @@ impl<L: Reduce, R: Reduce> Reduce for Add<L, R> {
     }
 }
 
-impl<L: Reduce> Reduce for Add<L, Zero> {
+impl<L: Reduce, T> Reduce for Add<L, Zero<T>> {
     type Output = Reduced<L>;
 
     #[inline]
@@ -217,7 +290,7 @@ impl<L: Reduce> Reduce for Add<L, Zero> {
     }
 }
 
-impl<R: Reduce> Reduce for Add<Zero, R> {
+impl<T, R: Reduce> Reduce for Add<Zero<T>, R> {
     type Output = Reduced<R>;
 
     #[inline]
@@ -226,28 +299,28 @@ impl<R: Reduce> Reduce for Add<Zero, R> {
     }
 }
 
-impl<L: Reduce> Reduce for Add<L, One> {
-    type Output = Add<Reduced<L>, One>;
+impl<L: Reduce, T> Reduce for Add<L, One<T>> {
+    type Output = Add<Reduced<L>, One<T>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Add(self.0.reduce(), One)
+        Add(self.0.reduce(), self.1)
     }
 }
 
-impl<R: Reduce> Reduce for Add<One, R> {
-    type Output = Add<One, Reduced<R>>;
+impl<T, R: Reduce> Reduce for Add<One<T>, R> {
+    type Output = Add<One<T>, Reduced<R>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Add(One, self.1.reduce())
+        Add(self.0, self.1.reduce())
     }
 }
 
-reduce_simple!(Add<Zero, Zero> => Zero);
-reduce_simple!(Add<One, Zero> => One);
-reduce_simple!(Add<Zero, One> => One);
-reduce_simple!(Add<One, One> => Self);
+reduce_simple!({A, B} Add<Zero<A>, Zero<B>> => Zero<A>);
+reduce_simple!({A, B} Add<One<A>, Zero<B>> => One<A>);
+reduce_simple!({A, B} Add<Zero<A>, One<B>> => One<B>);
+reduce_simple!({A, B} Add<One<A>, One<B>> => Self);
 
 //////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -260,7 +333,7 @@ impl<L: Reduce, R: Reduce> Reduce for Sub<L, R> {
     }
 }
 
-impl<L: Reduce> Reduce for Sub<L, Zero> {
+impl<L: Reduce, T> Reduce for Sub<L, Zero<T>> {
     type Output = Reduced<L>;
 
     #[inline]
@@ -269,7 +342,7 @@ impl<L: Reduce> Reduce for Sub<L, Zero> {
     }
 }
 
-impl<R: Reduce> Reduce for Sub<Zero, R>
+impl<T, R: Reduce> Reduce for Sub<Zero<T>, R>
 where
     Neg<R>: Reduce,
 {
@@ -281,37 +354,36 @@ where
     }
 }
 
-impl<L: Reduce> Reduce for Sub<L, One> {
-    type Output = Sub<Reduced<L>, One>;
+impl<L: Reduce, T> Reduce for Sub<L, One<T>> {
+    type Output = Sub<Reduced<L>, One<T>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Sub(self.0.reduce(), One)
+        Sub(self.0.reduce(), self.1)
     }
 }
 
-impl<R: Reduce> Reduce for Sub<One, R> {
-    type Output = Sub<One, Reduced<R>>;
+impl<T, R: Reduce> Reduce for Sub<One<T>, R> {
+    type Output = Sub<One<T>, Reduced<R>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Sub(One, self.1.reduce())
+        Sub(self.0, self.1.reduce())
     }
 }
 
-impl Reduce for Sub<Zero, One> {
-    type Output = Neg<One>;
+impl<A, B> Reduce for Sub<Zero<A>, One<B>> {
+    type Output = Neg<One<B>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Neg(One)
+        Neg(self.1)
     }
 }
 
-reduce_simple!(Sub<Zero, Zero> => Zero);
-reduce_simple!(Sub<One, Zero> => One);
-// TODO: Check
-reduce_simple!(Sub<One, One> => Zero);
+reduce_simple!({A, B} Sub<Zero<A>, Zero<B>> => Zero<A>);
+reduce_simple!({A, B} Sub<One<A>, Zero<B>> => One<A>);
+reduce_simple!({A, B} Sub<One<A>, One<B>> => Zero<A>);
 
 //////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -324,7 +396,7 @@ impl<L: Reduce, R: Reduce> Reduce for Mul<L, R> {
     }
 }
 
-impl<L: Reduce> Reduce for Mul<L, One> {
+impl<L: Reduce, T> Reduce for Mul<L, One<T>> {
     type Output = Reduced<L>;
 
     #[inline]
@@ -333,7 +405,7 @@ impl<L: Reduce> Reduce for Mul<L, One> {
     }
 }
 
-impl<R: Reduce> Reduce for Mul<One, R> {
+impl<T, R: Reduce> Reduce for Mul<One<T>, R> {
     type Output = Reduced<R>;
 
     #[inline]
@@ -342,12 +414,12 @@ impl<R: Reduce> Reduce for Mul<One, R> {
     }
 }
 
-reduce_simple!({L: Reduce} Mul<L, Zero> => Zero);
-reduce_simple!({R: Reduce} Mul<Zero, R> => Zero);
-reduce_simple!(Mul<Zero, Zero> => Zero);
-reduce_simple!(Mul<Zero, One> => Zero);
-reduce_simple!(Mul<One, Zero> => Zero);
-reduce_simple!(Mul<One, One> => One);
+reduce_simple!({L: Reduce, T} Mul<L, Zero<T>> => Zero<T>);
+reduce_simple!({T, R: Reduce} Mul<Zero<T>, R> => Zero<T>);
+reduce_simple!({A, B} Mul<Zero<A>, Zero<B>> => Zero<A>);
+reduce_simple!({A, B} Mul<Zero<A>, One<B>> => Zero<A>);
+reduce_simple!({A, B} Mul<One<A>, Zero<B>> => Zero<B>);
+reduce_simple!({A, B} Mul<One<A>, One<B>> => One<A>);
 
 //////////////////////////////////////////////////////////////////////////////////////////////////////
 
@@ -360,7 +432,7 @@ impl<L: Reduce, R: Reduce> Reduce for Div<L, R> {
     }
 }
 
-impl<L: Reduce> Reduce for Div<L, One> {
+impl<L: Reduce, T> Reduce for Div<L, One<T>> {
     type Output = Reduced<L>;
 
     #[inline]
@@ -369,21 +441,50 @@ impl<L: Reduce> Reduce for Div<L, One> {
     }
 }
 
-impl<R: Reduce> Reduce for Div<One, R> {
-    type Output = Div<One, Reduced<R>>;
+impl<T, R: Reduce> Reduce for Div<One<T>, R> {
+    type Output = Div<One<T>, Reduced<R>>;
 
     #[inline]
     fn reduce(self) -> Self::Output {
-        Div(One, self.1.reduce())
+        Div(self.0, self.1.reduce())
     }
 }
 
-reduce_simple!(Div<One, One> => Self);
-reduce_simple!(Div<Zero, One> => Zero);
-reduce_simple!({R: Reduce} Div<Zero, R> => Zero);
-// Unimplemented:
-//   - Div<One, Zero>
-//   - Div<L, Zero>
-//   - Div<Zero, Zero>
+reduce_simple!({A, B} Div<One<A>, One<B>> => One<A>);
+reduce_simple!({A, B} Div<Zero<A>, One<B>> => Zero<A>);
+reduce_simple!({T, R: Reduce} Div<Zero<T>, R> => Zero<T>);
+
+/// Never implemented, so that reducing a division by `Zero` is a compile-time error.
+/// # Examples
+/// ```compile_fail
+/// use typing::expr::{constant::zero, var::var};
+/// use typing::tags::X;
+///
+/// (var(1.0, X) / zero::<f64>()).reduce();
+/// ```
+#[diagnostic::on_unimplemented(message = "division by `{Self}` in an expression")]
+pub trait NonZero {}
+
+macro_rules! reduce_div_zero {
+    ($({$($c:tt $(: $c0:ident)?),*} $typ:ty;)*) => {$(
+        impl<$($c $(: $c0)?),*> Reduce for $typ
+        where
+            Zero<T>: NonZero,
+        {
+            type Output = Self;
+
+            #[inline(always)]
+            fn reduce(self) -> Self::Output {
+                self
+            }
+        }
+    )*};
+}
+
+reduce_div_zero!(
+    {L: Reduce, T} Div<L, Zero<T>>;
+    {A, T} Div<One<A>, Zero<T>>;
+    {A, T} Div<Zero<A>, Zero<T>>;
+);
 
 //////////////////////////////////////////////////////////////////////////////////////////////////////
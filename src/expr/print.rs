@@ -0,0 +1,597 @@
+use crate::expr::call::Call;
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{Cos, Exp, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use crate::expr::share::Let;
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, One, Zero};
+use crate::int::Int;
+use std::fmt::{self, Debug, Display, Formatter, Write};
+
+/// Notations in which expressions can be printed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Notation {
+    /// Infix notation with the operators of Rust, e.g. `2 * x + sin(y)`, except that `^` is
+    /// exponentiation and bitwise xor is written as `xor`.
+    Infix,
+    /// LaTeX math, e.g. `2 \cdot x + \sin\left(y\right)`.
+    Latex,
+    /// S-expressions, e.g. `(+ (* 2 x) (sin y))`.
+    SExpr,
+}
+
+/// Printing of expressions, where variables are named by the `Debug` output of their tags.
+/// Infix and LaTeX output only contain the parentheses required by the precedence of the
+/// operators.
+pub trait Print {
+    /// Binding strength of the outermost operator of the expression in the given notation.
+    fn precedence(&self, notation: Notation) -> u8;
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result;
+}
+
+// precedences of the operators, following Rust with exponentiation binding stronger than negation
+const COMPARE: u8 = 0;
+const BIT_OR: u8 = 1;
+const BIT_XOR: u8 = 2;
+const BIT_AND: u8 = 3;
+const SHIFT: u8 = 4;
+const SUM: u8 = 5;
+const PRODUCT: u8 = 6;
+const PREFIX: u8 = 7;
+const POWER: u8 = 8;
+pub(crate) const ATOM: u8 = 9;
+
+impl<T: Print> Display for Expr<T> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.0.print(Notation::Infix, f)
+    }
+}
+
+impl<T: Print> Expr<T> {
+    /// Print the expression as LaTeX math.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(1.0, X);
+    /// let y = var(2.0, Y);
+    /// let f = (x + y) / (x * y.sin());
+    ///
+    /// assert_eq!(f.to_string(), "(X + Y) / (X * sin(Y))");
+    /// assert_eq!(f.to_latex(), r"\frac{X + Y}{X \cdot \sin\left(Y\right)}");
+    /// ```
+    pub fn to_latex(&self) -> String {
+        render(&self.0, Notation::Latex)
+    }
+
+    /// Print the expression as an S-expression.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = var(1.0, X);
+    /// let y = var(2.0, Y);
+    ///
+    /// assert_eq!((-x * 2.0 - y.powi(3)).to_sexpr(), "(- (* (- X) 2) (powi Y 3))");
+    /// ```
+    pub fn to_sexpr(&self) -> String {
+        render(&self.0, Notation::SExpr)
+    }
+}
+
+pub(crate) fn render(e: &dyn Print, notation: Notation) -> String {
+    let mut s = String::new();
+    e.print(notation, &mut s)
+        .expect("writing to a String can't fail");
+    s
+}
+
+impl<T: Print + ?Sized> Print for &T {
+    #[inline]
+    fn precedence(&self, notation: Notation) -> u8 {
+        (**self).precedence(notation)
+    }
+
+    #[inline]
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        (**self).print(notation, f)
+    }
+}
+
+/// Print `e`, wrapped in parentheses if its precedence is below `min`.
+fn operand<E: Print>(e: &E, min: u8, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+    if e.precedence(notation) >= min {
+        e.print(notation, f)
+    } else if notation == Notation::Latex {
+        f.write_str(r"\left(")?;
+        e.print(notation, f)?;
+        f.write_str(r"\right)")
+    } else {
+        f.write_char('(')?;
+        e.print(notation, f)?;
+        f.write_char(')')
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T, Tag: Default + Debug, D> Print for Var<T, Tag, D> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    fn print(&self, _: Notation, f: &mut dyn Write) -> fmt::Result {
+        write!(f, "{:?}", Tag::default())
+    }
+}
+
+impl<T, Tag: Default + Debug, D> Print for Placeholder<T, Tag, D> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    fn print(&self, _: Notation, f: &mut dyn Write) -> fmt::Result {
+        write!(f, "{:?}", Tag::default())
+    }
+}
+
+// negative constants bind like a negation
+impl<T: Display> Print for Const<T> {
+    fn precedence(&self, _: Notation) -> u8 {
+        if self.0.to_string().starts_with('-') {
+            PREFIX
+        } else {
+            ATOM
+        }
+    }
+
+    fn print(&self, _: Notation, f: &mut dyn Write) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+macro_rules! print_identity {
+    ($($name:ident => $value:literal),*) => {$(
+        impl<T> Print for $name<T> {
+            #[inline]
+            fn precedence(&self, _: Notation) -> u8 {
+                ATOM
+            }
+
+            fn print(&self, _: Notation, f: &mut dyn Write) -> fmt::Result {
+                f.write_str($value)
+            }
+        }
+    )*};
+}
+
+print_identity!(Zero => "0", One => "1");
+
+macro_rules! print_prefix {
+    ($($name:ident => $infix:literal, $latex:literal, $sexpr:literal);*) => {$(
+        impl<T: Print> Print for $name<T> {
+            #[inline]
+            fn precedence(&self, _: Notation) -> u8 {
+                PREFIX
+            }
+
+            fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+                match notation {
+                    Notation::Infix => f.write_str($infix)?,
+                    Notation::Latex => f.write_str($latex)?,
+                    Notation::SExpr => {
+                        write!(f, "({} ", $sexpr)?;
+                        self.0.print(notation, f)?;
+                        return f.write_char(')');
+                    }
+                }
+                // -(-x) keeps its parentheses, while -x^2 doesn't need any
+                operand(&self.0, PREFIX + 1, notation, f)
+            }
+        }
+    )*};
+}
+
+print_prefix!(
+    Neg => "-", "-", "-";
+    Not => "!", r"\lnot ", "!"
+);
+
+// binary operators are left associative, so the right operand also needs parentheses at the same
+// precedence unless any operator at that precedence can be regrouped, e.g. x + (y - z) = x + y - z
+// but x * (y % z) != x * y % z
+macro_rules! print_binary {
+    ($(
+        $name:ident($prec:ident, $assoc:literal) => $infix:literal, $latex:literal, $sexpr:literal
+    );*) => {$(
+        impl<L: Print, R: Print> Print for $name<L, R> {
+            #[inline]
+            fn precedence(&self, _: Notation) -> u8 {
+                $prec
+            }
+
+            fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+                let op = match notation {
+                    Notation::Infix => $infix,
+                    Notation::Latex => $latex,
+                    Notation::SExpr => {
+                        write!(f, "({} ", $sexpr)?;
+                        self.0.print(notation, f)?;
+                        f.write_char(' ')?;
+                        self.1.print(notation, f)?;
+                        return f.write_char(')');
+                    }
+                };
+                operand(&self.0, $prec, notation, f)?;
+                write!(f, " {} ", op)?;
+                operand(&self.1, if $assoc { $prec } else { $prec + 1 }, notation, f)
+            }
+        }
+    )*};
+}
+
+print_binary!(
+    Add(SUM, true) => "+", "+", "+";
+    Sub(SUM, false) => "-", "-", "-";
+    Mul(PRODUCT, false) => "*", r"\cdot", "*";
+    Rem(PRODUCT, false) => "%", r"\bmod", "%";
+    Shl(SHIFT, false) => "<<", r"\ll", "<<";
+    Shr(SHIFT, false) => ">>", r"\gg", ">>";
+    BitAnd(BIT_AND, true) => "&", r"\land", "&";
+    BitXor(BIT_XOR, true) => "xor", r"\oplus", "xor";
+    BitOr(BIT_OR, true) => "|", r"\lor", "|"
+);
+
+// comparisons can't be chained, so both operands need parentheses at the same precedence
+macro_rules! print_compare {
+    ($($name:ident => $infix:literal, $latex:literal);*) => {$(
+        impl<L: Print, R: Print> Print for $name<L, R> {
+            #[inline]
+            fn precedence(&self, _: Notation) -> u8 {
+                COMPARE
+            }
+
+            fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+                let op = match notation {
+                    Notation::Infix => $infix,
+                    Notation::Latex => $latex,
+                    Notation::SExpr => {
+                        write!(f, "({} ", $infix)?;
+                        self.0.print(notation, f)?;
+                        f.write_char(' ')?;
+                        self.1.print(notation, f)?;
+                        return f.write_char(')');
+                    }
+                };
+                operand(&self.0, COMPARE + 1, notation, f)?;
+                write!(f, " {} ", op)?;
+                operand(&self.1, COMPARE + 1, notation, f)
+            }
+        }
+    )*};
+}
+
+print_compare!(
+    Less => "<", "<";
+    LessEq => "<=", r"\le";
+    Greater => ">", ">";
+    GreaterEq => ">=", r"\ge";
+    Equal => "==", "=";
+    NotEqual => "!=", r"\ne"
+);
+
+impl<C: Print, A: Print, B: Print> Print for Select<C, A, B> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        match notation {
+            Notation::Infix => {
+                f.write_str("select(")?;
+                self.0.print(notation, f)?;
+                f.write_str(", ")?;
+                self.1.print(notation, f)?;
+                f.write_str(", ")?;
+                self.2.print(notation, f)?;
+                f.write_char(')')
+            }
+            Notation::Latex => {
+                f.write_str(r"\begin{cases} ")?;
+                self.1.print(notation, f)?;
+                f.write_str(" & ")?;
+                self.0.print(notation, f)?;
+                f.write_str(r" \\ ")?;
+                self.2.print(notation, f)?;
+                f.write_str(r" & \text{otherwise} \end{cases}")
+            }
+            Notation::SExpr => {
+                f.write_str("(select ")?;
+                self.0.print(notation, f)?;
+                f.write_char(' ')?;
+                self.1.print(notation, f)?;
+                f.write_char(' ')?;
+                self.2.print(notation, f)?;
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+impl<L: Print, R: Print> Print for Div<L, R> {
+    #[inline]
+    fn precedence(&self, notation: Notation) -> u8 {
+        // fractions only need parentheses as the base of a power
+        match notation {
+            Notation::Latex => POWER,
+            _ => PRODUCT,
+        }
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        match notation {
+            Notation::Infix => {
+                operand(&self.0, PRODUCT, notation, f)?;
+                f.write_str(" / ")?;
+                operand(&self.1, PRODUCT + 1, notation, f)
+            }
+            Notation::Latex => {
+                f.write_str(r"\frac{")?;
+                self.0.print(notation, f)?;
+                f.write_str("}{")?;
+                self.1.print(notation, f)?;
+                f.write_char('}')
+            }
+            Notation::SExpr => {
+                f.write_str("(/ ")?;
+                self.0.print(notation, f)?;
+                f.write_char(' ')?;
+                self.1.print(notation, f)?;
+                f.write_char(')')
+            }
+        }
+    }
+}
+
+macro_rules! print_function {
+    ($($name:ident => $infix:literal, $latex:literal);*) => {$(
+        impl<T: Print> Print for $name<T> {
+            #[inline]
+            fn precedence(&self, _: Notation) -> u8 {
+                ATOM
+            }
+
+            fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+                match notation {
+                    Notation::Infix => write!(f, "{}(", $infix)?,
+                    Notation::Latex => write!(f, r"{}\left(", $latex)?,
+                    Notation::SExpr => write!(f, "({} ", $infix)?,
+                }
+                self.0.print(notation, f)?;
+                match notation {
+                    Notation::Latex => f.write_str(r"\right)"),
+                    _ => f.write_char(')'),
+                }
+            }
+        }
+    )*};
+}
+
+print_function!(
+    Sin => "sin", r"\sin";
+    Cos => "cos", r"\cos";
+    Ln => "ln", r"\ln";
+    Tanh => "tanh", r"\tanh"
+);
+
+impl<T: Print> Print for Exp<T> {
+    #[inline]
+    fn precedence(&self, notation: Notation) -> u8 {
+        // e^{x} needs parentheses as the base of a power
+        match notation {
+            Notation::Latex => POWER,
+            _ => ATOM,
+        }
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        match notation {
+            Notation::Infix => f.write_str("exp(")?,
+            Notation::Latex => f.write_str("e^{")?,
+            Notation::SExpr => f.write_str("(exp ")?,
+        }
+        self.0.print(notation, f)?;
+        match notation {
+            Notation::Latex => f.write_char('}'),
+            _ => f.write_char(')'),
+        }
+    }
+}
+
+impl<T: Print> Print for Sqrt<T> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        match notation {
+            Notation::Infix => f.write_str("sqrt(")?,
+            Notation::Latex => f.write_str(r"\sqrt{")?,
+            Notation::SExpr => f.write_str("(sqrt ")?,
+        }
+        self.0.print(notation, f)?;
+        match notation {
+            Notation::Latex => f.write_char('}'),
+            _ => f.write_char(')'),
+        }
+    }
+}
+
+// user-defined functions are printed with the debug name of the function type
+impl<F: Default + Debug, T: Print> Print for Call<F, T> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    #[inline]
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        print_call(&format!("{:?}", F::default()), &self.1, notation, f)
+    }
+}
+
+/// Print the application of the user-defined function `name` to `arg`.
+pub(crate) fn print_call(
+    name: &str,
+    arg: &dyn Print,
+    notation: Notation,
+    f: &mut dyn Write,
+) -> fmt::Result {
+    match notation {
+        Notation::Infix => write!(f, "{}(", name)?,
+        Notation::Latex => write!(f, r"\operatorname{{{}}}\left(", name)?,
+        Notation::SExpr => write!(f, "({} ", name)?,
+    }
+    arg.print(notation, f)?;
+    match notation {
+        Notation::Latex => f.write_str(r"\right)"),
+        _ => f.write_char(')'),
+    }
+}
+
+// bindings are printed as blocks in Rust and as the evaluation of the body at the value in LaTeX
+impl<Tag: Default + Debug, V: Print, B: Print> Print for Let<Tag, V, B> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        ATOM
+    }
+
+    #[inline]
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        print_let(
+            &format!("{:?}", Tag::default()),
+            &self.1,
+            &self.2,
+            notation,
+            f,
+        )
+    }
+}
+
+/// Print the binding of `value` to the placeholder named `tag` in `body`.
+pub(crate) fn print_let(
+    tag: &str,
+    value: &dyn Print,
+    body: &dyn Print,
+    notation: Notation,
+    f: &mut dyn Write,
+) -> fmt::Result {
+    match notation {
+        Notation::Infix => {
+            write!(f, "{{ let {} = ", tag)?;
+            value.print(notation, f)?;
+            f.write_str("; ")?;
+            body.print(notation, f)?;
+            f.write_str(" }")
+        }
+        Notation::Latex => {
+            f.write_str(r"\left. ")?;
+            body.print(notation, f)?;
+            write!(f, r" \right|_{{{} = ", tag)?;
+            value.print(notation, f)?;
+            f.write_char('}')
+        }
+        Notation::SExpr => {
+            write!(f, "(let ({} ", tag)?;
+            value.print(notation, f)?;
+            f.write_str(") ")?;
+            body.print(notation, f)?;
+            f.write_char(')')
+        }
+    }
+}
+
+/// Print `base^exponent`, where the exponent is printed by `exponent`.
+fn power<B: Print>(
+    base: &B,
+    notation: Notation,
+    f: &mut dyn Write,
+    exponent: impl FnOnce(&mut dyn Write) -> fmt::Result,
+) -> fmt::Result {
+    if notation == Notation::Latex {
+        operand(base, ATOM, notation, f)?;
+        f.write_str("^{")?;
+        exponent(f)?;
+        f.write_char('}')
+    } else {
+        operand(base, POWER + 1, notation, f)?;
+        f.write_char('^')?;
+        exponent(f)
+    }
+}
+
+impl<T: Print> Print for Powi<T> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        POWER
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        if notation == Notation::SExpr {
+            f.write_str("(powi ")?;
+            self.0.print(notation, f)?;
+            return write!(f, " {})", self.1);
+        }
+        let n = self.1;
+        power(&self.0, notation, f, |f| {
+            if n < 0 && notation == Notation::Infix {
+                write!(f, "({})", n)
+            } else {
+                write!(f, "{}", n)
+            }
+        })
+    }
+}
+
+// printed the same as the power with a runtime exponent
+impl<T: Print, N: Int> Print for Pow<T, N> {
+    #[inline]
+    fn precedence(&self, notation: Notation) -> u8 {
+        Powi(&self.0, N::VALUE.into()).precedence(notation)
+    }
+
+    #[inline]
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        Powi(&self.0, N::VALUE.into()).print(notation, f)
+    }
+}
+
+impl<L: Print, R: Print> Print for Powf<L, R> {
+    #[inline]
+    fn precedence(&self, _: Notation) -> u8 {
+        POWER
+    }
+
+    fn print(&self, notation: Notation, f: &mut dyn Write) -> fmt::Result {
+        if notation == Notation::SExpr {
+            f.write_str("(pow ")?;
+            self.0.print(notation, f)?;
+            f.write_char(' ')?;
+            self.1.print(notation, f)?;
+            return f.write_char(')');
+        }
+        let exponent = &self.1;
+        power(&self.0, notation, f, |f| match notation {
+            // exponentiation is right associative
+            Notation::Infix => operand(exponent, POWER, notation, f),
+            _ => exponent.print(notation, f),
+        })
+    }
+}
@@ -0,0 +1,935 @@
+//! Sharing of subexpressions with `let`-bindings. A [`Let`] evaluates its value once and binds it
+//! to a placeholder in its body, so a subexpression used several times is only computed once.
+//! Bindings can be written with [`Expr::let_in`] or found automatically by [`Expr::cse`], which
+//! binds the subexpressions that occur more than once, as far as their type identifies them.
+//!
+//! The derivative of a binding is the binding of the derivative, using the chain rule through the
+//! bound placeholder, and reduction keeps bindings that are still used, so both preserve sharing.
+//!
+//! [`Let`]: struct.Let.html
+//! [`Expr::let_in`]: ../struct.Expr.html#method.let_in
+//! [`Expr::cse`]: ../struct.Expr.html#method.cse
+use crate::boolean::{Bool, BoolIfElse, False, IfElse, True};
+use crate::expr::call::Call;
+use crate::expr::cond::{Branch, Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::deriv::{Deriv, Dv};
+use crate::expr::elementary::{Cos, Exp, IntPow, IntPowOut, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::env::{EvalWith, EvalWithOut};
+use crate::expr::free::{DependsOn, DependsOnOut};
+use crate::expr::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use crate::expr::subst::{Subst, SubstOut};
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, Expression, One, Reduce, Reduced, Zero};
+use crate::func::Func;
+use crate::ident::{Id, IsEq, TypeEq, TypeId};
+use crate::int::{Int, IntAdd, Plus, Pred, Succ, P1, Z0};
+use crate::option::{NoneType, SomeType};
+use crate::{T0, T1};
+use std::fmt::{Debug, Formatter};
+
+/// Binding of the value of `V` to the placeholder tagged with `Tag` in the body `B`.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct Let<Tag, V, B>(pub Tag, pub V, pub B);
+
+/// Tag of the `N`-th subexpression bound by [`Expr::cse`], printed as `t0`, `t1`, ...
+///
+/// [`Expr::cse`]: ../struct.Expr.html#method.cse
+#[derive(Copy, Clone, Default)]
+pub struct Tmp<N>(N);
+
+impl<N: Int> Debug for Tmp<N> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "t{}", N::VALUE)
+    }
+}
+
+// the ids of the tags in `tags` are nested pairs starting with a bit, so a pair in front keeps the
+// temporaries apart from them
+impl<N: IntKey> TypeId for Tmp<N> {
+    type Id = ((T0, T0), N::Key);
+}
+
+/// Distinguishable encoding of type-level integers.
+#[doc(hidden)]
+pub trait IntKey {
+    type Key;
+}
+
+impl IntKey for Z0 {
+    type Key = T0;
+}
+
+impl<N: IntKey> IntKey for Succ<N> {
+    type Key = (T1, N::Key);
+}
+
+impl<N: IntKey> IntKey for Pred<N> {
+    type Key = (T0, N::Key);
+}
+
+/// The type of the value of an expression, which is needed for the placeholder of a binding
+/// without evaluating anything.
+#[doc(hidden)]
+pub trait ValueType {
+    type Output;
+}
+
+pub type ValueOf<T> = <T as ValueType>::Output;
+
+impl<T> Expr<T> {
+    /// Bind the value of the expression to the placeholder tagged with `tag` in `body`, which is
+    /// given the placeholder.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::tags::{T, X, Y};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    /// let f = (x * y + y).let_in(T, |t| t * t - x);
+    /// assert_eq!(f.eval(), 79.0);
+    /// assert_eq!(f.to_string(), "{ let T = X * Y + Y; T * T - X }");
+    ///
+    /// // d/dx (t^2 - x) with t = xy + y
+    /// assert_eq!(f.deriv(X).eval(), 53.0);
+    /// assert_eq!(f.deriv(X).to_string(), "{ let T = X * Y + Y; -1 + (T + T) * Y }");
+    /// ```
+    #[inline]
+    pub fn let_in<Tag, B, F>(self, tag: Tag, body: F) -> Expr<Let<Tag, T, B>>
+    where
+        T: ValueType,
+        F: FnOnce(Expr<Placeholder<ValueOf<T>, Tag>>) -> Expr<B>,
+    {
+        Expr(Let(tag, self.0, body(Expr(Placeholder::new())).0))
+    }
+}
+
+impl<Tag, V, B> Expression for Let<Tag, V, B>
+where
+    V: Expression,
+    B: EvalWith<((Tag, V::Output), ())>,
+{
+    type Output = EvalWithOut<B, ((Tag, V::Output), ())>;
+
+    #[inline]
+    fn eval(self) -> Self::Output {
+        self.2.eval_with(&((self.0, self.1.eval()), ()))
+    }
+}
+
+impl<Tag, V, B, Env> EvalWith<Env> for Let<Tag, V, B>
+where
+    Tag: Clone,
+    V: EvalWith<Env>,
+    B: ValueType + for<'a> EvalWith<((Tag, V::Output), &'a Env), Output = ValueOf<B>>,
+{
+    type Output = ValueOf<B>;
+
+    #[inline]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        self.2
+            .eval_with(&((self.0.clone(), self.1.eval_with(env)), env))
+    }
+}
+
+// substituting the bound tag itself stops at the binding, which shadows it
+impl<Tag, V, B, TagB, R> Subst<TagB, R> for Let<Tag, V, B>
+where
+    Tag: TypeEq<TagB>,
+    V: Subst<TagB, R>,
+    B: SubstScope<TagB, R, IsEq<Tag, TagB>>,
+{
+    type Output = Let<Tag, SubstOut<V, TagB, R>, B::Output>;
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        Let(
+            self.0,
+            self.1.subst(replacement),
+            self.2.subst_scope(replacement),
+        )
+    }
+}
+
+#[doc(hidden)]
+pub trait SubstScope<Tag, R, Shadowed> {
+    type Output;
+    fn subst_scope(self, replacement: &R) -> Self::Output;
+}
+
+impl<B, Tag, R> SubstScope<Tag, R, True> for B {
+    type Output = B;
+
+    #[inline(always)]
+    fn subst_scope(self, _: &R) -> Self::Output {
+        self
+    }
+}
+
+impl<B: Subst<Tag, R>, Tag, R> SubstScope<Tag, R, False> for B {
+    type Output = SubstOut<B, Tag, R>;
+
+    #[inline]
+    fn subst_scope(self, replacement: &R) -> Self::Output {
+        self.subst(replacement)
+    }
+}
+
+// bindings which aren't used by the reduced body anymore are dropped, e.g. in derivatives
+impl<Tag, V: Branch, B: Branch> Reduce for Let<Tag, V, B>
+where
+    B::Output: DependsOn<Tag>,
+    Let<Tag, V::Output, B::Output>: Unbind<DependsOnOut<B::Output, Tag>>,
+{
+    type Output = <Let<Tag, V::Output, B::Output> as Unbind<DependsOnOut<B::Output, Tag>>>::Output;
+
+    #[inline]
+    fn reduce(self) -> Self::Output {
+        Let(self.0, self.1.branch(), self.2.branch()).unbind()
+    }
+}
+
+#[doc(hidden)]
+pub trait Unbind<Used> {
+    type Output;
+    fn unbind(self) -> Self::Output;
+}
+
+impl<Tag, V, B> Unbind<True> for Let<Tag, V, B> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Output {
+        self
+    }
+}
+
+impl<Tag, V, B> Unbind<False> for Let<Tag, V, B> {
+    type Output = B;
+
+    #[inline(always)]
+    fn unbind(self) -> Self::Output {
+        self.2
+    }
+}
+
+// d/dx (let t = v in b) = let t = v in (db/dx + db/dt dv/dx)
+impl<Tag, V, B, X> Deriv<X> for Let<Tag, V, B>
+where
+    Tag: Clone,
+    V: Deriv<X> + Clone,
+    B: Deriv<X> + Deriv<Tag>,
+    Mul<Dv<B, Tag>, Dv<V, X>>: Reduce,
+    Add<Dv<B, X>, Reduced<Mul<Dv<B, Tag>, Dv<V, X>>>>: Reduce,
+    Let<Tag, V, Reduced<Add<Dv<B, X>, Reduced<Mul<Dv<B, Tag>, Dv<V, X>>>>>>: Reduce,
+{
+    #[allow(clippy::type_complexity)]
+    type Output = Reduced<Let<Tag, V, Reduced<Add<Dv<B, X>, Reduced<Mul<Dv<B, Tag>, Dv<V, X>>>>>>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        let chain = Mul(Deriv::<Tag>::deriv(&self.2), self.1.deriv()).reduce();
+        let body = Add(Deriv::<X>::deriv(&self.2), chain).reduce();
+        Let(self.0.clone(), self.1.clone(), body).reduce()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Common subexpression elimination, which binds the subexpressions occurring more than once.
+pub trait Cse {
+    type Output;
+    fn cse(self) -> Self::Output;
+}
+
+pub type CseOut<T> = <T as Cse>::Output;
+
+impl<T: CseFrom<Z0>> Cse for T {
+    type Output = T::Output;
+
+    #[inline]
+    fn cse(self) -> Self::Output {
+        self.cse_from()
+    }
+}
+
+impl<T> Expr<T> {
+    /// Bind every subexpression that occurs more than once to a temporary, innermost ones first,
+    /// so that each of them is evaluated once. Variables are identified by their tag only, so all
+    /// variables with the same tag are assumed to hold the same value.
+    ///
+    /// Subexpressions are identified by their type, so nodes holding values that aren't part of
+    /// their type can't be compared: constants, `powi` with its exponent, calls of user-defined
+    /// functions and existing bindings. Neither these nor any subexpression containing one of
+    /// them is shared, e.g. `sin(x * 2 + y)` and `x.powi(2) + y` are never bound, while their
+    /// operands without such nodes still are. Integer powers written with `pow` are shared, and
+    /// other subexpressions can be bound by hand with [`let_in`](#method.let_in).
+    ///
+    /// Every step counts the occurrences of each node in the whole expression, so compile times
+    /// grow steeply with its size: the elimination in the first derivative of `sin(x y)/(x + y)`
+    /// takes about a second to compile, and in the second derivative over ten. Since derivatives
+    /// keep bindings, it's much cheaper to eliminate before differentiating.
+    /// # Examples
+    /// ```
+    /// use typing::expr::var::var;
+    /// use typing::tags::{T, X, Y};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(3.0, Y);
+    /// let f = (x * y + y) * (x * y + y) - x * y;
+    ///
+    /// let g = f.cse();
+    /// assert_eq!(g.to_string(), "{ let t0 = X * Y; { let t1 = t0 + Y; t1 * t1 - t0 } }");
+    /// assert_eq!(g.eval(), f.eval());
+    /// assert_eq!(g.deriv(X).eval(), f.deriv(X).eval());
+    ///
+    /// // the constant keeps the sines from being shared, so they're bound by hand
+    /// let h = (x * 2.0 + y).sin() * (x * 2.0 + y).sin();
+    /// assert_eq!(h.cse().to_string(), "sin(X * 2 + Y) * sin(X * 2 + Y)");
+    /// let h = (x * 2.0 + y).sin().let_in(T, |t| t * t);
+    /// assert_eq!(h.to_string(), "{ let T = sin(X * 2 + Y); T * T }");
+    /// ```
+    #[inline]
+    pub fn cse(self) -> Expr<CseOut<T>>
+    where
+        T: Cse,
+    {
+        Expr(self.0.cse())
+    }
+}
+
+/// One step of the elimination, binding the first repeated subexpression to `Tmp<N>`.
+#[doc(hidden)]
+pub trait CseFrom<N> {
+    type Output;
+    fn cse_from(self) -> Self::Output;
+}
+
+impl<T, N> CseFrom<N> for T
+where
+    T: Repeated<T> + CseStep<N, <T as Repeated<T>>::Output>,
+{
+    type Output = <T as CseStep<N, <T as Repeated<T>>::Output>>::Output;
+
+    #[inline]
+    fn cse_from(self) -> Self::Output {
+        self.cse_step()
+    }
+}
+
+#[doc(hidden)]
+pub trait CseStep<N, Found> {
+    type Output;
+    fn cse_step(self) -> Self::Output;
+}
+
+impl<T, N> CseStep<N, NoneType> for T {
+    type Output = T;
+
+    #[inline(always)]
+    fn cse_step(self) -> Self::Output {
+        self
+    }
+}
+
+impl<T, N, S, K> CseStep<N, SomeType<S>> for T
+where
+    N: Default,
+    S: NodeKey<Key = SomeType<K>> + ValueType,
+    T: Replace<K, Placeholder<ValueOf<S>, Tmp<N>>, Found = SomeType<S>>,
+    <T as Replace<K, Placeholder<ValueOf<S>, Tmp<N>>>>::Output: CseFrom<Succ<N>>,
+{
+    #[allow(clippy::type_complexity)]
+    type Output = Let<
+        Tmp<N>,
+        S,
+        <<T as Replace<K, Placeholder<ValueOf<S>, Tmp<N>>>>::Output as CseFrom<Succ<N>>>::Output,
+    >;
+
+    #[inline]
+    fn cse_step(self) -> Self::Output {
+        let (body, SomeType(value)) = self.replace(&Placeholder::new());
+        Let(Tmp(N::default()), value, body.cse_from())
+    }
+}
+
+/// Structural key of a node as a [`SomeType`] of a paren of bits, or [`NoneType`] if it contains a
+/// value that isn't part of its type.
+///
+/// [`SomeType`]: ../../option/struct.SomeType.html
+/// [`NoneType`]: ../../option/struct.NoneType.html
+#[doc(hidden)]
+pub trait NodeKey {
+    type Key;
+}
+
+pub type KeyOf<T> = <T as NodeKey>::Key;
+
+/// Number of subexpressions with key `K`.
+#[doc(hidden)]
+pub trait Occurrences<K> {
+    type Output: Int;
+}
+
+/// The first subexpression in post-order which occurs more than once in `Root`, as an option.
+#[doc(hidden)]
+pub trait Repeated<Root> {
+    type Output;
+}
+
+/// Replacement of every subexpression with key `K` by `P`, also returning the first replaced one
+/// as an option.
+#[doc(hidden)]
+pub trait Replace<K, P> {
+    type Output;
+    type Found;
+    fn replace(self, p: &P) -> (Self::Output, Self::Found);
+}
+
+#[doc(hidden)]
+pub trait Replace2<K, P, Hit> {
+    type Output;
+    type Found;
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found);
+}
+
+impl<T, K, P> Replace<K, P> for T
+where
+    T: NodeKey,
+    T::Key: Matches<K>,
+    T: Replace2<K, P, <T::Key as Matches<K>>::Output>,
+{
+    type Output = <T as Replace2<K, P, <T::Key as Matches<K>>::Output>>::Output;
+    type Found = <T as Replace2<K, P, <T::Key as Matches<K>>::Output>>::Found;
+
+    #[inline(always)]
+    fn replace(self, p: &P) -> (Self::Output, Self::Found) {
+        self.replace2(p)
+    }
+}
+
+impl<T, K, P: Clone> Replace2<K, P, True> for T {
+    type Output = P;
+    type Found = SomeType<T>;
+
+    #[inline]
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+        (p.clone(), SomeType(self))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Whether a key is `SomeType<K>`.
+#[doc(hidden)]
+pub trait Matches<K> {
+    type Output: Bool;
+}
+
+impl<K> Matches<K> for NoneType {
+    type Output = False;
+}
+
+impl<J: TypeEq<K>, K> Matches<K> for SomeType<J> {
+    type Output = IsEq<J, K>;
+}
+
+/// Key of a node with the code `C` from the key of its operands.
+#[doc(hidden)]
+pub trait KeyWrap<C> {
+    type Output;
+}
+
+impl<C> KeyWrap<C> for NoneType {
+    type Output = NoneType;
+}
+
+impl<C, K> KeyWrap<C> for SomeType<K> {
+    type Output = SomeType<(C, K)>;
+}
+
+/// Key of a pair of operands.
+#[doc(hidden)]
+pub trait KeyPair<R> {
+    type Output;
+}
+
+impl<R> KeyPair<R> for NoneType {
+    type Output = NoneType;
+}
+
+impl<K> KeyPair<NoneType> for SomeType<K> {
+    type Output = NoneType;
+}
+
+impl<K, J> KeyPair<SomeType<J>> for SomeType<K> {
+    type Output = SomeType<(K, J)>;
+}
+
+/// The first of two options which is `SomeType`, which is evaluated for the found subexpressions.
+#[doc(hidden)]
+pub trait OrElse<R> {
+    type Output;
+    fn or_else(self, r: R) -> Self::Output;
+}
+
+type OrElseOut<L, R> = <L as OrElse<R>>::Output;
+
+impl<R> OrElse<R> for NoneType {
+    type Output = R;
+
+    #[inline(always)]
+    fn or_else(self, r: R) -> Self::Output {
+        r
+    }
+}
+
+impl<T, R> OrElse<R> for SomeType<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn or_else(self, _: R) -> Self::Output {
+        self
+    }
+}
+
+/// `P1` for `True` and `Z0` for `False`.
+#[doc(hidden)]
+pub trait BoolInt {
+    type Output: Int;
+}
+
+impl BoolInt for True {
+    type Output = P1;
+}
+
+impl BoolInt for False {
+    type Output = Z0;
+}
+
+/// Whether a count is at least two.
+#[doc(hidden)]
+pub trait Repeats {
+    type Output: Bool;
+}
+
+impl Repeats for Z0 {
+    type Output = False;
+}
+
+impl Repeats for Succ<Z0> {
+    type Output = False;
+}
+
+impl<N> Repeats for Succ<Succ<N>> {
+    type Output = True;
+}
+
+/// Number of times the node `S` with this key matches `K`.
+#[doc(hidden)]
+pub trait Hits<K> {
+    type Output: Int;
+}
+
+impl<S: NodeKey, K> Hits<K> for S
+where
+    S::Key: Matches<K>,
+    <S::Key as Matches<K>>::Output: BoolInt,
+{
+    type Output = <<S::Key as Matches<K>>::Output as BoolInt>::Output;
+}
+
+/// `SomeType<S>` if the node `S` with this key occurs more than once in `Root`.
+#[doc(hidden)]
+pub trait Candidate<S, Root> {
+    type Output;
+}
+
+impl<S, Root> Candidate<S, Root> for NoneType {
+    type Output = NoneType;
+}
+
+impl<K, S, Root> Candidate<S, Root> for SomeType<K>
+where
+    Root: Occurrences<K>,
+    <Root as Occurrences<K>>::Output: Repeats,
+    <<Root as Occurrences<K>>::Output as Repeats>::Output: BoolIfElse<SomeType<S>, NoneType>,
+{
+    type Output =
+        IfElse<<<Root as Occurrences<K>>::Output as Repeats>::Output, SomeType<S>, NoneType>;
+}
+
+type CandidateOut<S, Root> = <KeyOf<S> as Candidate<S, Root>>::Output;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+// distinct codes for the node types as little-endian binary numbers
+#[rustfmt::skip]
+mod code {
+    use crate::{T0, T1};
+
+    macro_rules! node_codes {
+        ([$($bits:ident)*] $name:ident $($rest:ident)*) => {
+            pub type $name = node_codes!(@tuple $($bits)*);
+            node_codes!(@next [] [$($bits)*] $($rest)*);
+        };
+        ([$($bits:ident)*]) => {};
+        (@tuple $b:ident) => { $b };
+        (@tuple $b:ident $($bs:ident)+) => { ($b, node_codes!(@tuple $($bs)+)) };
+        (@next [$($l:ident)*] [T0 $($r:ident)*] $($rest:ident)*) => {
+            node_codes!([$($l)* T1 $($r)*] $($rest)*);
+        };
+        (@next [$($l:ident)*] [T1 $($r:ident)*] $($rest:ident)*) => {
+            node_codes!(@next [$($l)* T0] [$($r)*] $($rest)*);
+        };
+    }
+
+    node_codes!([T0 T0 T0 T0 T0 T0]
+        Var Placeholder Zero One Neg Not Sin Cos Exp Ln Sqrt Tanh Pow Add Sub Mul Div Rem BitAnd
+        BitOr BitXor Shl Shr Powf Less LessEq Greater GreaterEq Equal NotEqual Select
+    );
+}
+
+macro_rules! share_leaf {
+    ($({$($c:tt)*} $typ:ty => $key:ty;)*) => {$(
+        impl<$($c)*> NodeKey for $typ {
+            type Key = $key;
+        }
+
+        impl<$($c)* K> Occurrences<K> for $typ {
+            type Output = Z0;
+        }
+
+        impl<$($c)* Root> Repeated<Root> for $typ {
+            type Output = NoneType;
+        }
+
+        impl<$($c)* K, P> Replace2<K, P, False> for $typ {
+            type Output = Self;
+            type Found = NoneType;
+
+            #[inline(always)]
+            fn replace2(self, _: &P) -> (Self::Output, Self::Found) {
+                (self, NoneType)
+            }
+        }
+    )*};
+}
+
+share_leaf!(
+    {T, Tag: TypeId, D,} Var<T, Tag, D> => SomeType<(code::Var, Id<Tag>)>;
+    {T, Tag: TypeId, D,} Placeholder<T, Tag, D> => SomeType<(code::Placeholder, Id<Tag>)>;
+    {T,} Const<T> => NoneType;
+    {T,} Zero<T> => SomeType<code::Zero>;
+    {T,} One<T> => SomeType<code::One>;
+    {Tag, V, B,} Let<Tag, V, B> => NoneType;
+);
+
+macro_rules! share_unary {
+    ($($name:ident)*) => {$(
+        impl<T: NodeKey> NodeKey for $name<T>
+        where
+            T::Key: KeyWrap<code::$name>,
+        {
+            type Key = <T::Key as KeyWrap<code::$name>>::Output;
+        }
+
+        impl<T: Occurrences<K>, K> Occurrences<K> for $name<T>
+        where
+            Self: Hits<K>,
+            <Self as Hits<K>>::Output: IntAdd<T::Output>,
+        {
+            type Output = Plus<<Self as Hits<K>>::Output, T::Output>;
+        }
+
+        impl<T: Repeated<Root>, Root> Repeated<Root> for $name<T>
+        where
+            Self: NodeKey,
+            KeyOf<Self>: Candidate<Self, Root>,
+            T::Output: OrElse<CandidateOut<Self, Root>>,
+        {
+            type Output = <T::Output as OrElse<CandidateOut<Self, Root>>>::Output;
+        }
+
+        impl<T: Replace<K, P>, K, P> Replace2<K, P, False> for $name<T> {
+            type Output = $name<T::Output>;
+            type Found = T::Found;
+
+            #[inline]
+            fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+                let (x, found) = self.0.replace(p);
+                ($name(x), found)
+            }
+        }
+    )*};
+}
+
+share_unary!(Neg Not Sin Cos Exp Ln Sqrt Tanh);
+
+macro_rules! share_binary {
+    ($($name:ident)*) => {$(
+        impl<L: NodeKey, R: NodeKey> NodeKey for $name<L, R>
+        where
+            L::Key: KeyPair<R::Key>,
+            <L::Key as KeyPair<R::Key>>::Output: KeyWrap<code::$name>,
+        {
+            type Key = <<L::Key as KeyPair<R::Key>>::Output as KeyWrap<code::$name>>::Output;
+        }
+
+        impl<L: Occurrences<K>, R: Occurrences<K>, K> Occurrences<K> for $name<L, R>
+        where
+            Self: Hits<K>,
+            L::Output: IntAdd<R::Output>,
+            <Self as Hits<K>>::Output: IntAdd<Plus<L::Output, R::Output>>,
+        {
+            type Output = Plus<<Self as Hits<K>>::Output, Plus<L::Output, R::Output>>;
+        }
+
+        impl<L: Repeated<Root>, R: Repeated<Root>, Root> Repeated<Root> for $name<L, R>
+        where
+            Self: NodeKey,
+            KeyOf<Self>: Candidate<Self, Root>,
+            L::Output: OrElse<R::Output>,
+            <L::Output as OrElse<R::Output>>::Output: OrElse<CandidateOut<Self, Root>>,
+        {
+            type Output = OrElseOut<OrElseOut<L::Output, R::Output>, CandidateOut<Self, Root>>;
+        }
+
+        impl<L, R, K, P> Replace2<K, P, False> for $name<L, R>
+        where
+            L: Replace<K, P>,
+            R: Replace<K, P>,
+            L::Found: OrElse<R::Found>,
+        {
+            type Output = $name<L::Output, R::Output>;
+            type Found = <L::Found as OrElse<R::Found>>::Output;
+
+            #[inline]
+            fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+                let (l, found_l) = self.0.replace(p);
+                let (r, found_r) = self.1.replace(p);
+                ($name(l, r), found_l.or_else(found_r))
+            }
+        }
+    )*};
+}
+
+share_binary!(Add Sub Mul Div Rem BitAnd BitOr BitXor Shl Shr Powf);
+share_binary!(Less LessEq Greater GreaterEq Equal NotEqual);
+
+impl<T: NodeKey, N: IntKey> NodeKey for Pow<T, N>
+where
+    T::Key: KeyWrap<(code::Pow, N::Key)>,
+{
+    type Key = <T::Key as KeyWrap<(code::Pow, N::Key)>>::Output;
+}
+
+impl<T: Occurrences<K>, N, K> Occurrences<K> for Pow<T, N>
+where
+    Self: Hits<K>,
+    <Self as Hits<K>>::Output: IntAdd<T::Output>,
+{
+    type Output = Plus<<Self as Hits<K>>::Output, T::Output>;
+}
+
+impl<T: Repeated<Root>, N, Root> Repeated<Root> for Pow<T, N>
+where
+    Self: NodeKey,
+    KeyOf<Self>: Candidate<Self, Root>,
+    T::Output: OrElse<CandidateOut<Self, Root>>,
+{
+    type Output = <T::Output as OrElse<CandidateOut<Self, Root>>>::Output;
+}
+
+impl<T: Replace<K, P>, N, K, P> Replace2<K, P, False> for Pow<T, N> {
+    type Output = Pow<T::Output, N>;
+    type Found = T::Found;
+
+    #[inline]
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+        let (x, found) = self.0.replace(p);
+        (Pow(x, self.1), found)
+    }
+}
+
+// the exponent of powi and user-defined functions aren't part of the type, so these nodes and
+// every node containing them are never shared, but their operand may be
+impl<T> NodeKey for Powi<T> {
+    type Key = NoneType;
+}
+
+impl<T: Occurrences<K>, K> Occurrences<K> for Powi<T> {
+    type Output = T::Output;
+}
+
+impl<T: Repeated<Root>, Root> Repeated<Root> for Powi<T> {
+    type Output = T::Output;
+}
+
+impl<T: Replace<K, P>, K, P> Replace2<K, P, False> for Powi<T> {
+    type Output = Powi<T::Output>;
+    type Found = T::Found;
+
+    #[inline]
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+        let (x, found) = self.0.replace(p);
+        (Powi(x, self.1), found)
+    }
+}
+
+impl<F, T> NodeKey for Call<F, T> {
+    type Key = NoneType;
+}
+
+impl<F, T: Occurrences<K>, K> Occurrences<K> for Call<F, T> {
+    type Output = T::Output;
+}
+
+impl<F, T: Repeated<Root>, Root> Repeated<Root> for Call<F, T> {
+    type Output = T::Output;
+}
+
+impl<F, T: Replace<K, P>, K, P> Replace2<K, P, False> for Call<F, T> {
+    type Output = Call<F, T::Output>;
+    type Found = T::Found;
+
+    #[inline]
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+        let (x, found) = self.1.replace(p);
+        (Call(self.0, x), found)
+    }
+}
+
+impl<C: NodeKey, A: NodeKey, B: NodeKey> NodeKey for Select<C, A, B>
+where
+    A::Key: KeyPair<B::Key>,
+    C::Key: KeyPair<<A::Key as KeyPair<B::Key>>::Output>,
+    <C::Key as KeyPair<<A::Key as KeyPair<B::Key>>::Output>>::Output: KeyWrap<code::Select>,
+{
+    #[allow(clippy::type_complexity)]
+    type Key = <<C::Key as KeyPair<<A::Key as KeyPair<B::Key>>::Output>>::Output as KeyWrap<
+        code::Select,
+    >>::Output;
+}
+
+impl<C, A, B, K> Occurrences<K> for Select<C, A, B>
+where
+    C: Occurrences<K>,
+    A: Occurrences<K>,
+    B: Occurrences<K>,
+    Self: Hits<K>,
+    A::Output: IntAdd<B::Output>,
+    C::Output: IntAdd<Plus<A::Output, B::Output>>,
+    <Self as Hits<K>>::Output: IntAdd<Plus<C::Output, Plus<A::Output, B::Output>>>,
+{
+    type Output = Plus<<Self as Hits<K>>::Output, Plus<C::Output, Plus<A::Output, B::Output>>>;
+}
+
+impl<C, A, B, Root> Repeated<Root> for Select<C, A, B>
+where
+    C: Repeated<Root>,
+    A: Repeated<Root>,
+    B: Repeated<Root>,
+    Self: NodeKey,
+    KeyOf<Self>: Candidate<Self, Root>,
+    A::Output: OrElse<B::Output>,
+    C::Output: OrElse<<A::Output as OrElse<B::Output>>::Output>,
+    <C::Output as OrElse<<A::Output as OrElse<B::Output>>::Output>>::Output:
+        OrElse<CandidateOut<Self, Root>>,
+{
+    #[allow(clippy::type_complexity)]
+    type Output =
+        <<C::Output as OrElse<<A::Output as OrElse<B::Output>>::Output>>::Output as OrElse<
+            CandidateOut<Self, Root>,
+        >>::Output;
+}
+
+impl<C, A, B, K, P> Replace2<K, P, False> for Select<C, A, B>
+where
+    C: Replace<K, P>,
+    A: Replace<K, P>,
+    B: Replace<K, P>,
+    A::Found: OrElse<B::Found>,
+    C::Found: OrElse<<A::Found as OrElse<B::Found>>::Output>,
+{
+    type Output = Select<C::Output, A::Output, B::Output>;
+    type Found = <C::Found as OrElse<<A::Found as OrElse<B::Found>>::Output>>::Output;
+
+    #[inline]
+    fn replace2(self, p: &P) -> (Self::Output, Self::Found) {
+        let (c, found_c) = self.0.replace(p);
+        let (a, found_a) = self.1.replace(p);
+        let (b, found_b) = self.2.replace(p);
+        (Select(c, a, b), found_c.or_else(found_a.or_else(found_b)))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! value_type {
+    ($({$($c:tt)*} $typ:ty => $value:ty;)*) => {$(
+        impl<$($c)*> ValueType for $typ {
+            type Output = $value;
+        }
+    )*};
+}
+
+value_type!(
+    {T, Tag, D} Var<T, Tag, D> => T;
+    {T, Tag, D} Placeholder<T, Tag, D> => T;
+    {T} Const<T> => T;
+    {T} Zero<T> => T;
+    {T} One<T> => T;
+    {Tag, V, B: ValueType} Let<Tag, V, B> => ValueOf<B>;
+    {T: ValueType} Sin<T> => ValueOf<T>;
+    {T: ValueType} Cos<T> => ValueOf<T>;
+    {T: ValueType} Exp<T> => ValueOf<T>;
+    {T: ValueType} Ln<T> => ValueOf<T>;
+    {T: ValueType} Sqrt<T> => ValueOf<T>;
+    {T: ValueType} Tanh<T> => ValueOf<T>;
+    {T: ValueType} Powi<T> => ValueOf<T>;
+    {L: ValueType, R} Powf<L, R> => ValueOf<L>;
+    {L, R} Less<L, R> => bool;
+    {L, R} LessEq<L, R> => bool;
+    {L, R} Greater<L, R> => bool;
+    {L, R} GreaterEq<L, R> => bool;
+    {L, R} Equal<L, R> => bool;
+    {L, R} NotEqual<L, R> => bool;
+    {C, A: ValueType, B} Select<C, A, B> => ValueOf<A>;
+    {F: Func<ValueOf<T>>, T: ValueType} Call<F, T> => F::Output;
+);
+
+impl<T: ValueType> ValueType for Neg<T>
+where
+    ValueOf<T>: std::ops::Neg,
+{
+    type Output = <ValueOf<T> as std::ops::Neg>::Output;
+}
+
+impl<T: ValueType> ValueType for Not<T>
+where
+    ValueOf<T>: std::ops::Not,
+{
+    type Output = <ValueOf<T> as std::ops::Not>::Output;
+}
+
+impl<T: ValueType, N> ValueType for Pow<T, N>
+where
+    ValueOf<T>: IntPow<N>,
+{
+    type Output = IntPowOut<ValueOf<T>, N>;
+}
+
+macro_rules! value_type_binop {
+    ($($name:ident)*) => {$(
+        impl<L: ValueType, R: ValueType> ValueType for $name<L, R>
+        where
+            ValueOf<L>: std::ops::$name<ValueOf<R>>,
+        {
+            type Output = <ValueOf<L> as std::ops::$name<ValueOf<R>>>::Output;
+        }
+    )*};
+}
+
+value_type_binop!(Add Sub Mul Div Rem BitAnd BitOr BitXor Shl Shr);
@@ -0,0 +1,861 @@
+use crate::boolean::{And, Bool, BoolAnd, False, True};
+use crate::expr::call::Call;
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{Cos, Exp, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use crate::expr::share::Let;
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, One, Reduce, Reduced, Zero};
+use crate::ident::{IsEq, TypeEq};
+use crate::num::Num;
+
+/// Canonicalisation of an expression. Unlike [`Reduce`], this works bottom-up through the whole
+/// tree: once the children of a node are simplified, the node itself is reduced and then the
+/// first matching rewrite rule for its type is applied. The rules are
+/// - double negation: `-(-x) → x`
+/// - sign normalisation: `x + -y → x - y`, `-x + y → y - x`, `x - -y → x + y` and negations are
+///   pulled out of products and quotients, `-x * y → -(x * y)`
+/// - constant folding of literals, i.e. constants, zeros and ones: `2 * 3 → 6`
+/// - literals are moved to the left of products and nested literals are folded:
+///   `(x * 2) * 3 → 6 * x`
+/// - like terms of variables with the same tag are collected: `x + x → 2 * x`,
+///   `2 * x + 3 * x → 5 * x`, `x - x → 0` and `x / x → 1`
+///
+/// [`Reduce`]: ../trait.Reduce.html
+pub trait Simplify {
+    type Output;
+    fn simplify(self) -> Self::Output;
+}
+
+pub type Simplified<T> = <T as Simplify>::Output;
+
+/// Classification of expression nodes, used to select the rewrite rules of [`Simplify`].
+///
+/// [`Simplify`]: trait.Simplify.html
+pub trait Shape {
+    /// Whether the node is a negation.
+    type IsNeg: Bool;
+    /// Whether the node is a [`Literal`](trait.Literal.html).
+    type IsLit: Bool;
+    /// Whether the node is a variable or a placeholder.
+    type IsVar: Bool;
+    /// Whether the node is a product with a literal on the left.
+    type IsScaled: Bool;
+    /// Whether the node is a [`Term`](trait.Term.html).
+    type IsTerm: Bool;
+}
+
+/// Expressions with a value that is known without evaluating any variables, i.e. constants,
+/// zeros and ones.
+pub trait Literal {
+    type Value;
+    fn value(self) -> Self::Value;
+}
+
+/// A variable with a literal coefficient, i.e. `x` or `c * x`.
+pub trait Term {
+    type Tag;
+    type Coeff;
+    type Var;
+    fn split(self) -> (Self::Coeff, Self::Var);
+}
+
+// the rules of a node are tried in order like the arms of a TypeMatch, and the first rule that
+// matches is applied to the node
+#[doc(hidden)]
+pub trait Rules {
+    type List;
+}
+
+#[doc(hidden)]
+pub trait Rule<N> {
+    type Matches: Bool;
+}
+
+#[doc(hidden)]
+pub trait RuleApply<N> {
+    type Output;
+    fn apply(node: N) -> Self::Output;
+}
+
+#[doc(hidden)]
+pub trait Rewrite<List> {
+    type Output;
+    fn rewrite(self) -> Self::Output;
+}
+
+#[doc(hidden)]
+pub trait Rewrite2<List, Marker> {
+    type Output;
+    fn rewrite2(self) -> Self::Output;
+}
+
+type RuleList<N> = <N as Rules>::List;
+type Rewritten<N, List> = <N as Rewrite<List>>::Output;
+type Rewritten2<N, R, Rs> = <N as Rewrite2<(R, Rs), <R as Rule<N>>::Matches>>::Output;
+
+/// Canonical form of a node whose children are already canonical.
+#[doc(hidden)]
+pub trait Canon {
+    type Output;
+    fn canon(self) -> Self::Output;
+}
+
+type Canonical<T> = <T as Canon>::Output;
+
+impl<N> Rewrite<()> for N {
+    type Output = N;
+
+    #[inline(always)]
+    fn rewrite(self) -> Self::Output {
+        self
+    }
+}
+
+impl<N, R, Rs> Rewrite<(R, Rs)> for N
+where
+    R: Rule<N>,
+    N: Rewrite2<(R, Rs), R::Matches>,
+{
+    type Output = Rewritten2<N, R, Rs>;
+
+    #[inline(always)]
+    fn rewrite(self) -> Self::Output {
+        self.rewrite2()
+    }
+}
+
+impl<N, R: RuleApply<N>, Rs> Rewrite2<(R, Rs), True> for N {
+    type Output = R::Output;
+
+    #[inline(always)]
+    fn rewrite2(self) -> Self::Output {
+        R::apply(self)
+    }
+}
+
+impl<N: Rewrite<Rs>, R, Rs> Rewrite2<(R, Rs), False> for N {
+    type Output = Rewritten<N, Rs>;
+
+    #[inline(always)]
+    fn rewrite2(self) -> Self::Output {
+        self.rewrite()
+    }
+}
+
+impl<N> Canon for N
+where
+    N: Reduce,
+    Reduced<N>: Rules + Rewrite<RuleList<Reduced<N>>>,
+{
+    type Output = Rewritten<Reduced<N>, RuleList<Reduced<N>>>;
+
+    #[inline(always)]
+    fn canon(self) -> Self::Output {
+        self.reduce().rewrite()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T: Simplify> Simplify for Expr<T> {
+    type Output = Expr<Simplified<T>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Expr(self.0.simplify())
+    }
+}
+
+impl<A: Simplify, B: Simplify> Simplify for (A, B) {
+    type Output = (A::Output, B::Output);
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        (self.0.simplify(), self.1.simplify())
+    }
+}
+
+impl Simplify for () {
+    type Output = ();
+
+    #[inline(always)]
+    fn simplify(self) -> Self::Output {}
+}
+
+macro_rules! simplify_leaf {
+    ($({$($c:tt),*} $typ:ty, is_lit: $lit:ident, is_var: $var:ident;)*) => {$(
+        impl<$($c),*> Simplify for $typ {
+            type Output = Self;
+
+            #[inline(always)]
+            fn simplify(self) -> Self::Output {
+                self
+            }
+        }
+
+        impl<$($c),*> Shape for $typ {
+            type IsNeg = False;
+            type IsLit = $lit;
+            type IsVar = $var;
+            type IsScaled = False;
+            type IsTerm = $var;
+        }
+
+        impl<$($c),*> Rules for $typ {
+            type List = ();
+        }
+    )*};
+}
+
+simplify_leaf!(
+    {T, Tag, D} Var<T, Tag, D>, is_lit: False, is_var: True;
+    {T, Tag, D} Placeholder<T, Tag, D>, is_lit: False, is_var: True;
+    {T} Const<T>, is_lit: True, is_var: False;
+    {T} Zero<T>, is_lit: True, is_var: False;
+    {T} One<T>, is_lit: True, is_var: False;
+);
+
+macro_rules! simplify_unary {
+    ($($name:ident),*) => {$(
+        impl<T: Simplify> Simplify for $name<T>
+        where
+            $name<Simplified<T>>: Canon,
+        {
+            type Output = Canonical<$name<Simplified<T>>>;
+
+            #[inline]
+            fn simplify(self) -> Self::Output {
+                $name(self.0.simplify()).canon()
+            }
+        }
+
+        impl<T> Shape for $name<T> {
+            type IsNeg = False;
+            type IsLit = False;
+            type IsVar = False;
+            type IsScaled = False;
+            type IsTerm = False;
+        }
+
+        impl<T> Rules for $name<T> {
+            type List = ();
+        }
+    )*};
+}
+
+simplify_unary!(Sin, Cos, Exp, Ln, Sqrt, Tanh);
+
+impl<T: Simplify> Simplify for Powi<T>
+where
+    Powi<Simplified<T>>: Canon,
+{
+    type Output = Canonical<Powi<Simplified<T>>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Powi(self.0.simplify(), self.1).canon()
+    }
+}
+
+impl<T> Shape for Powi<T> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+impl<T> Rules for Powi<T> {
+    type List = ();
+}
+
+impl<T: Simplify, N> Simplify for Pow<T, N>
+where
+    Pow<Simplified<T>, N>: Canon,
+{
+    type Output = Canonical<Pow<Simplified<T>, N>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Pow(self.0.simplify(), self.1).canon()
+    }
+}
+
+impl<T, N> Shape for Pow<T, N> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+impl<T, N> Rules for Pow<T, N> {
+    type List = ();
+}
+
+impl<F, T: Simplify> Simplify for Call<F, T>
+where
+    Call<F, Simplified<T>>: Canon,
+{
+    type Output = Canonical<Call<F, Simplified<T>>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Call(self.0, self.1.simplify()).canon()
+    }
+}
+
+impl<F, T> Shape for Call<F, T> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+impl<F, T> Rules for Call<F, T> {
+    type List = ();
+}
+
+impl<Tag, V: Simplify, B: Simplify> Simplify for Let<Tag, V, B>
+where
+    Let<Tag, Simplified<V>, Simplified<B>>: Canon,
+{
+    type Output = Canonical<Let<Tag, Simplified<V>, Simplified<B>>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Let(self.0, self.1.simplify(), self.2.simplify()).canon()
+    }
+}
+
+impl<Tag, V, B> Shape for Let<Tag, V, B> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+impl<Tag, V, B> Rules for Let<Tag, V, B> {
+    type List = ();
+}
+
+impl<T: Simplify> Simplify for Neg<T>
+where
+    Neg<Simplified<T>>: Canon,
+{
+    type Output = Canonical<Neg<Simplified<T>>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Neg(self.0.simplify()).canon()
+    }
+}
+
+impl<T> Shape for Neg<T> {
+    type IsNeg = True;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+impl<T> Rules for Neg<T> {
+    type List = (NegNeg, (FoldLit, ()));
+}
+
+macro_rules! simplify_binary {
+    ($($name:ident),*) => {$(
+        impl<L: Simplify, R: Simplify> Simplify for $name<L, R>
+        where
+            $name<Simplified<L>, Simplified<R>>: Canon,
+        {
+            type Output = Canonical<$name<Simplified<L>, Simplified<R>>>;
+
+            #[inline]
+            fn simplify(self) -> Self::Output {
+                $name(self.0.simplify(), self.1.simplify()).canon()
+            }
+        }
+    )*};
+}
+
+simplify_binary!(Add, Sub, Mul, Div, Powf);
+
+macro_rules! shape_binary {
+    ($($name:ident),*) => {$(
+        impl<L, R> Shape for $name<L, R> {
+            type IsNeg = False;
+            type IsLit = False;
+            type IsVar = False;
+            type IsScaled = False;
+            type IsTerm = False;
+        }
+    )*};
+}
+
+shape_binary!(Add, Sub, Div, Powf);
+
+impl<L: Shape, R: Shape> Shape for Mul<L, R>
+where
+    L::IsLit: BoolAnd<R::IsVar>,
+{
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = L::IsLit;
+    type IsTerm = And<L::IsLit, R::IsVar>;
+}
+
+impl<L, R> Rules for Add<L, R> {
+    type List = (AddNeg, (NegAdd, (FoldLit, (CollectTerms, ()))));
+}
+
+impl<L, R> Rules for Sub<L, R> {
+    type List = (SubNeg, (FoldLit, (CollectTerms, ())));
+}
+
+impl<L, R> Rules for Mul<L, R> {
+    type List = (FoldLit, (NegLeft, (NegRight, (LitRight, (NestedLit, ())))));
+}
+
+impl<L, R> Rules for Div<L, R> {
+    type List = (FoldLit, (NegLeft, (NegRight, (CollectTerms, ()))));
+}
+
+impl<L, R> Rules for Powf<L, R> {
+    type List = ();
+}
+
+// operators without reduction or rewrite rules only simplify their operands
+impl<T: Simplify> Simplify for Not<T> {
+    type Output = Not<Simplified<T>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Not(self.0.simplify())
+    }
+}
+
+impl<T> Shape for Not<T> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+macro_rules! simplify_operands {
+    ($($name:ident),*) => {$(
+        impl<L: Simplify, R: Simplify> Simplify for $name<L, R> {
+            type Output = $name<Simplified<L>, Simplified<R>>;
+
+            #[inline]
+            fn simplify(self) -> Self::Output {
+                $name(self.0.simplify(), self.1.simplify())
+            }
+        }
+    )*};
+}
+
+simplify_operands!(Rem, BitAnd, BitOr, BitXor, Shl, Shr);
+shape_binary!(Rem, BitAnd, BitOr, BitXor, Shl, Shr);
+simplify_operands!(Less, LessEq, Greater, GreaterEq, Equal, NotEqual);
+shape_binary!(Less, LessEq, Greater, GreaterEq, Equal, NotEqual);
+
+impl<C: Simplify, A: Simplify, B: Simplify> Simplify for Select<C, A, B> {
+    type Output = Select<Simplified<C>, Simplified<A>, Simplified<B>>;
+
+    #[inline]
+    fn simplify(self) -> Self::Output {
+        Select(self.0.simplify(), self.1.simplify(), self.2.simplify())
+    }
+}
+
+impl<C, A, B> Shape for Select<C, A, B> {
+    type IsNeg = False;
+    type IsLit = False;
+    type IsVar = False;
+    type IsScaled = False;
+    type IsTerm = False;
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<T> Literal for Const<T> {
+    type Value = T;
+
+    #[inline(always)]
+    fn value(self) -> Self::Value {
+        self.0
+    }
+}
+
+impl<T: Num> Literal for Zero<T> {
+    type Value = T;
+
+    #[inline(always)]
+    fn value(self) -> Self::Value {
+        T::ZERO
+    }
+}
+
+impl<T: Num> Literal for One<T> {
+    type Value = T;
+
+    #[inline(always)]
+    fn value(self) -> Self::Value {
+        T::ONE
+    }
+}
+
+impl<T, Tag, D> Term for Var<T, Tag, D> {
+    type Tag = Tag;
+    type Coeff = One<T>;
+    type Var = Self;
+
+    #[inline(always)]
+    fn split(self) -> (Self::Coeff, Self::Var) {
+        (One::new(), self)
+    }
+}
+
+impl<T, Tag, D> Term for Placeholder<T, Tag, D> {
+    type Tag = Tag;
+    type Coeff = One<T>;
+    type Var = Self;
+
+    #[inline(always)]
+    fn split(self) -> (Self::Coeff, Self::Var) {
+        (One::new(), self)
+    }
+}
+
+impl<C, T, Tag, D> Term for Mul<C, Var<T, Tag, D>> {
+    type Tag = Tag;
+    type Coeff = C;
+    type Var = Var<T, Tag, D>;
+
+    #[inline(always)]
+    fn split(self) -> (Self::Coeff, Self::Var) {
+        (self.0, self.1)
+    }
+}
+
+impl<C, T, Tag, D> Term for Mul<C, Placeholder<T, Tag, D>> {
+    type Tag = Tag;
+    type Coeff = C;
+    type Var = Placeholder<T, Tag, D>;
+
+    #[inline(always)]
+    fn split(self) -> (Self::Coeff, Self::Var) {
+        (self.0, self.1)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// `-(-x) → x`
+#[doc(hidden)]
+pub struct NegNeg;
+
+impl<T: Shape> Rule<Neg<T>> for NegNeg {
+    type Matches = T::IsNeg;
+}
+
+impl<T> RuleApply<Neg<Neg<T>>> for NegNeg {
+    type Output = T;
+
+    #[inline(always)]
+    fn apply(node: Neg<Neg<T>>) -> Self::Output {
+        (node.0).0
+    }
+}
+
+/// Evaluation of operations on literals.
+#[doc(hidden)]
+pub struct FoldLit;
+
+impl<T: Shape> Rule<Neg<T>> for FoldLit {
+    type Matches = T::IsLit;
+}
+
+impl<T> RuleApply<Neg<T>> for FoldLit
+where
+    T: Literal,
+    T::Value: std::ops::Neg,
+{
+    type Output = Const<<T::Value as std::ops::Neg>::Output>;
+
+    #[inline(always)]
+    fn apply(node: Neg<T>) -> Self::Output {
+        Const(-node.0.value())
+    }
+}
+
+macro_rules! fold_lit {
+    ($($name:ident, $method:ident);*) => {$(
+        impl<L: Shape, R: Shape> Rule<$name<L, R>> for FoldLit
+        where
+            L::IsLit: BoolAnd<R::IsLit>,
+        {
+            type Matches = And<L::IsLit, R::IsLit>;
+        }
+
+        impl<L, R> RuleApply<$name<L, R>> for FoldLit
+        where
+            L: Literal,
+            R: Literal,
+            L::Value: std::ops::$name<R::Value>,
+        {
+            type Output = Const<<L::Value as std::ops::$name<R::Value>>::Output>;
+
+            #[inline(always)]
+            fn apply(node: $name<L, R>) -> Self::Output {
+                Const(std::ops::$name::$method(node.0.value(), node.1.value()))
+            }
+        }
+    )*};
+}
+
+fold_lit!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+/// `x + -y → x - y`
+#[doc(hidden)]
+pub struct AddNeg;
+
+impl<L, R: Shape> Rule<Add<L, R>> for AddNeg {
+    type Matches = R::IsNeg;
+}
+
+impl<L, R> RuleApply<Add<L, Neg<R>>> for AddNeg
+where
+    Sub<L, R>: Canon,
+{
+    type Output = Canonical<Sub<L, R>>;
+
+    #[inline(always)]
+    fn apply(node: Add<L, Neg<R>>) -> Self::Output {
+        Sub(node.0, (node.1).0).canon()
+    }
+}
+
+/// `-x + y → y - x`
+#[doc(hidden)]
+pub struct NegAdd;
+
+impl<L: Shape, R> Rule<Add<L, R>> for NegAdd {
+    type Matches = L::IsNeg;
+}
+
+impl<L, R> RuleApply<Add<Neg<L>, R>> for NegAdd
+where
+    Sub<R, L>: Canon,
+{
+    type Output = Canonical<Sub<R, L>>;
+
+    #[inline(always)]
+    fn apply(node: Add<Neg<L>, R>) -> Self::Output {
+        Sub(node.1, (node.0).0).canon()
+    }
+}
+
+/// `x - -y → x + y`
+#[doc(hidden)]
+pub struct SubNeg;
+
+impl<L, R: Shape> Rule<Sub<L, R>> for SubNeg {
+    type Matches = R::IsNeg;
+}
+
+impl<L, R> RuleApply<Sub<L, Neg<R>>> for SubNeg
+where
+    Add<L, R>: Canon,
+{
+    type Output = Canonical<Add<L, R>>;
+
+    #[inline(always)]
+    fn apply(node: Sub<L, Neg<R>>) -> Self::Output {
+        Add(node.0, (node.1).0).canon()
+    }
+}
+
+/// `-x * y → -(x * y)` and `-x / y → -(x / y)`
+#[doc(hidden)]
+pub struct NegLeft;
+
+/// `x * -y → -(x * y)` and `x / -y → -(x / y)`
+#[doc(hidden)]
+pub struct NegRight;
+
+macro_rules! pull_neg {
+    ($($name:ident),*) => {$(
+        impl<L: Shape, R> Rule<$name<L, R>> for NegLeft {
+            type Matches = L::IsNeg;
+        }
+
+        impl<L, R> RuleApply<$name<Neg<L>, R>> for NegLeft
+        where
+            $name<L, R>: Canon,
+            Neg<Canonical<$name<L, R>>>: Canon,
+        {
+            type Output = Canonical<Neg<Canonical<$name<L, R>>>>;
+
+            #[inline(always)]
+            fn apply(node: $name<Neg<L>, R>) -> Self::Output {
+                Neg($name((node.0).0, node.1).canon()).canon()
+            }
+        }
+
+        impl<L, R: Shape> Rule<$name<L, R>> for NegRight {
+            type Matches = R::IsNeg;
+        }
+
+        impl<L, R> RuleApply<$name<L, Neg<R>>> for NegRight
+        where
+            $name<L, R>: Canon,
+            Neg<Canonical<$name<L, R>>>: Canon,
+        {
+            type Output = Canonical<Neg<Canonical<$name<L, R>>>>;
+
+            #[inline(always)]
+            fn apply(node: $name<L, Neg<R>>) -> Self::Output {
+                Neg($name(node.0, (node.1).0).canon()).canon()
+            }
+        }
+    )*};
+}
+
+pull_neg!(Mul, Div);
+
+/// `x * c → c * x`
+#[doc(hidden)]
+pub struct LitRight;
+
+impl<L, R: Shape> Rule<Mul<L, R>> for LitRight {
+    type Matches = R::IsLit;
+}
+
+impl<L, R> RuleApply<Mul<L, R>> for LitRight
+where
+    Mul<R, L>: Canon,
+{
+    type Output = Canonical<Mul<R, L>>;
+
+    #[inline(always)]
+    fn apply(node: Mul<L, R>) -> Self::Output {
+        Mul(node.1, node.0).canon()
+    }
+}
+
+/// `a * (b * x) → (a * b) * x`
+#[doc(hidden)]
+pub struct NestedLit;
+
+impl<L: Shape, R: Shape> Rule<Mul<L, R>> for NestedLit
+where
+    L::IsLit: BoolAnd<R::IsScaled>,
+{
+    type Matches = And<L::IsLit, R::IsScaled>;
+}
+
+impl<A, B, T> RuleApply<Mul<A, Mul<B, T>>> for NestedLit
+where
+    Mul<A, B>: Canon,
+    Mul<Canonical<Mul<A, B>>, T>: Canon,
+{
+    type Output = Canonical<Mul<Canonical<Mul<A, B>>, T>>;
+
+    #[inline(always)]
+    fn apply(node: Mul<A, Mul<B, T>>) -> Self::Output {
+        let Mul(a, Mul(b, x)) = node;
+        Mul(Mul(a, b).canon(), x).canon()
+    }
+}
+
+/// `a * x + b * x → (a + b) * x`, `a * x - b * x → (a - b) * x` and `a * x / (b * x) → a / b`
+#[doc(hidden)]
+pub struct CollectTerms;
+
+#[doc(hidden)]
+pub trait Collect<Same> {
+    type Output;
+    fn collect(self) -> Self::Output;
+}
+
+macro_rules! collect_terms {
+    ($($name:ident),*) => {$(
+        impl<L: Shape, R: Shape> Rule<$name<L, R>> for CollectTerms
+        where
+            L::IsTerm: BoolAnd<R::IsTerm>,
+        {
+            type Matches = And<L::IsTerm, R::IsTerm>;
+        }
+
+        impl<L, R> RuleApply<$name<L, R>> for CollectTerms
+        where
+            L: Term,
+            R: Term,
+            L::Tag: TypeEq<R::Tag>,
+            $name<L, R>: Collect<IsEq<L::Tag, R::Tag>>,
+        {
+            type Output = <$name<L, R> as Collect<IsEq<L::Tag, R::Tag>>>::Output;
+
+            #[inline(always)]
+            fn apply(node: $name<L, R>) -> Self::Output {
+                node.collect()
+            }
+        }
+
+        impl<L, R> Collect<False> for $name<L, R> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn collect(self) -> Self::Output {
+                self
+            }
+        }
+    )*};
+}
+
+collect_terms!(Add, Sub, Div);
+
+macro_rules! collect_linear {
+    ($($name:ident),*) => {$(
+        impl<L, R> Collect<True> for $name<L, R>
+        where
+            L: Term,
+            R: Term,
+            $name<L::Coeff, R::Coeff>: Simplify,
+            Mul<Simplified<$name<L::Coeff, R::Coeff>>, L::Var>: Canon,
+        {
+            type Output = Canonical<Mul<Simplified<$name<L::Coeff, R::Coeff>>, L::Var>>;
+
+            #[inline(always)]
+            fn collect(self) -> Self::Output {
+                let (a, x) = self.0.split();
+                let (b, _) = self.1.split();
+                Mul($name(a, b).simplify(), x).canon()
+            }
+        }
+    )*};
+}
+
+collect_linear!(Add, Sub);
+
+impl<L, R> Collect<True> for Div<L, R>
+where
+    L: Term,
+    R: Term,
+    Div<L::Coeff, R::Coeff>: Simplify,
+{
+    type Output = Simplified<Div<L::Coeff, R::Coeff>>;
+
+    #[inline(always)]
+    fn collect(self) -> Self::Output {
+        Div(self.0.split().0, self.1.split().0).simplify()
+    }
+}
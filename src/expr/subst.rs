@@ -0,0 +1,42 @@
+use crate::expr::Expr;
+
+/// Substitution of every variable tagged with `Tag` in an expression by the expression `R`.
+/// The replacement is cloned for each occurrence of the variable.
+pub trait Subst<Tag, R> {
+    type Output;
+    fn subst(self, replacement: &R) -> Self::Output;
+}
+
+pub type SubstOut<T, Tag, R> = <T as Subst<Tag, R>>::Output;
+
+// variables first check whether their tag matches and then dispatch to SubstVar, like Lookup
+#[doc(hidden)]
+pub trait SubstVar<R, Marker> {
+    type Output;
+    fn subst_var(self, replacement: &R) -> Self::Output;
+}
+
+impl<T: Subst<Tag, R>, Tag, R> Subst<Tag, R> for Expr<T> {
+    type Output = Expr<T::Output>;
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        Expr(self.0.subst(replacement))
+    }
+}
+
+impl<A: Subst<Tag, R>, B: Subst<Tag, R>, Tag, R> Subst<Tag, R> for (A, B) {
+    type Output = (A::Output, B::Output);
+
+    #[inline]
+    fn subst(self, replacement: &R) -> Self::Output {
+        (self.0.subst(replacement), self.1.subst(replacement))
+    }
+}
+
+impl<Tag, R> Subst<Tag, R> for () {
+    type Output = ();
+
+    #[inline(always)]
+    fn subst(self, _: &R) -> Self::Output {}
+}
@@ -0,0 +1,598 @@
+use crate::boolean::{False, True};
+use crate::expr::call::{Call, Differentiable};
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{powi, Cos, Exp, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, Div, Mul, Neg, Rem, Sub};
+use crate::expr::share::Let;
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, Expression, One, Zero};
+use crate::func::Func;
+use crate::ident::{IsEq, TypeEq};
+use crate::int::Int;
+use crate::num::{Float, Num};
+use std::marker::PhantomData;
+
+/// Record of the operations of an evaluation for reverse-mode automatic differentiation. Each
+/// entry holds the partial derivatives of a node with respect to its (at most two) operands, where
+/// the first entries are the variables of the gradient. Operands that are constants have no
+/// entry, so that infinite partials of nodes that don't depend on them can't give NaN.
+#[derive(Clone, Debug, Default)]
+pub struct Tape<T> {
+    partials: Vec<[Option<(usize, T)>; 2]>,
+    // values bound by the enclosing `Let`s, innermost last
+    bound: Vec<Tracked<T>>,
+}
+
+/// Value computed on a [`Tape`], with the index of its node unless it's a constant.
+///
+/// [`Tape`]: struct.Tape.html
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tracked<T> {
+    pub value: T,
+    index: Option<usize>,
+}
+
+impl<T: Float> Tape<T> {
+    /// Create a tape with a node for each of `n` variables.
+    pub fn new(n: usize) -> Self {
+        Self {
+            partials: vec![[None, None]; n],
+            bound: Vec::new(),
+        }
+    }
+
+    /// A value that doesn't depend on any of the variables.
+    #[inline]
+    pub fn constant(value: T) -> Tracked<T> {
+        Tracked { value, index: None }
+    }
+
+    /// The value of the variable with the given index, or a constant if the index is `None`.
+    #[inline]
+    pub fn var(&self, value: T, index: Option<usize>) -> Tracked<T> {
+        Tracked { value, index }
+    }
+
+    /// Record a node with the partial derivatives of `value` with respect to its operands.
+    pub fn push(&mut self, value: T, operands: [(Tracked<T>, T); 2]) -> Tracked<T> {
+        if operands.iter().all(|(x, _)| x.index.is_none()) {
+            return Self::constant(value);
+        }
+        let [(a, da), (b, db)] = operands;
+        let entry = |x: Tracked<T>, d| x.index.map(|i| (i, d));
+        self.partials.push([entry(a, da), entry(b, db)]);
+        Tracked {
+            value,
+            index: Some(self.partials.len() - 1),
+        }
+    }
+
+    /// Record a node with a single operand.
+    #[inline]
+    pub fn push1(&mut self, value: T, x: Tracked<T>, dx: T) -> Tracked<T> {
+        self.push(value, [(x, dx), (Self::constant(T::ZERO), T::ZERO)])
+    }
+
+    /// Back-propagate the adjoints from `output`, giving the derivatives of `output` with respect
+    /// to every node on the tape.
+    pub fn adjoints(&self, output: Tracked<T>) -> Vec<T> {
+        let mut adjoints = vec![T::ZERO; self.partials.len()];
+        if let Some(out) = output.index {
+            adjoints[out] = T::ONE;
+            for i in (0..=out).rev() {
+                let adjoint = adjoints[i];
+                for &(j, d) in self.partials[i].iter().flatten() {
+                    adjoints[j] = adjoints[j] + adjoint * d;
+                }
+            }
+        }
+        adjoints
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Index of `Tag` in a paren of tags.
+#[doc(hidden)]
+pub trait Position<Tag> {
+    fn position() -> Option<usize>;
+}
+
+#[doc(hidden)]
+pub trait Position2<Tag, Marker> {
+    fn position2() -> Option<usize>;
+}
+
+impl<Tag> Position<Tag> for () {
+    #[inline(always)]
+    fn position() -> Option<usize> {
+        None
+    }
+}
+
+impl<Tag, K, B> Position<Tag> for (K, B)
+where
+    K: TypeEq<Tag>,
+    Self: Position2<Tag, IsEq<K, Tag>>,
+{
+    #[inline(always)]
+    fn position() -> Option<usize> {
+        Self::position2()
+    }
+}
+
+impl<Tag, K, B> Position2<Tag, True> for (K, B) {
+    #[inline(always)]
+    fn position2() -> Option<usize> {
+        Some(0)
+    }
+}
+
+impl<Tag, K, B: Position<Tag>> Position2<Tag, False> for (K, B) {
+    #[inline(always)]
+    fn position2() -> Option<usize> {
+        B::position().map(|i| i + 1)
+    }
+}
+
+/// Tags of the gradient inside a [`Let`] binding the placeholder tagged with `Tag`, whose value is
+/// on the stack of bound values of the tape.
+///
+/// [`Let`]: ../share/struct.Let.html
+#[doc(hidden)]
+pub struct Scope<Tag, Tags>(PhantomData<fn() -> (Tag, Tags)>);
+
+impl<Tag, K, Tags: Position<Tag>> Position<Tag> for Scope<K, Tags> {
+    #[inline(always)]
+    fn position() -> Option<usize> {
+        Tags::position()
+    }
+}
+
+/// Number of bindings between a placeholder tagged with `Tag` and the `Let` binding it.
+#[doc(hidden)]
+pub trait Depth<Tag> {
+    fn depth() -> usize;
+}
+
+#[doc(hidden)]
+pub trait Depth2<Tag, Marker> {
+    fn depth2() -> usize;
+}
+
+impl<Tag, K, Tags> Depth<Tag> for Scope<K, Tags>
+where
+    K: TypeEq<Tag>,
+    Self: Depth2<Tag, IsEq<K, Tag>>,
+{
+    #[inline(always)]
+    fn depth() -> usize {
+        Self::depth2()
+    }
+}
+
+impl<Tag, K, Tags> Depth2<Tag, True> for Scope<K, Tags> {
+    #[inline(always)]
+    fn depth2() -> usize {
+        0
+    }
+}
+
+impl<Tag, K, Tags: Depth<Tag>> Depth2<Tag, False> for Scope<K, Tags> {
+    #[inline(always)]
+    fn depth2() -> usize {
+        Tags::depth() + 1
+    }
+}
+
+/// Tags of the gradient in the scope of the placeholders in a labelled paren of `(tag, value)`
+/// pairs, whose values are pushed on the stack of bound values of the tape, so that the first pair
+/// is innermost.
+#[doc(hidden)]
+pub trait TapeEnv<T, Tags> {
+    type Scope;
+    fn bind(&self, tape: &mut Tape<T>);
+}
+
+impl<T, Tags> TapeEnv<T, Tags> for () {
+    type Scope = Tags;
+
+    #[inline(always)]
+    fn bind(&self, _: &mut Tape<T>) {}
+}
+
+impl<T, K, B, Tags> TapeEnv<T, Tags> for ((K, T), B)
+where
+    T: Float,
+    B: TapeEnv<T, Tags>,
+    Tags: Position<K>,
+{
+    type Scope = Scope<K, B::Scope>;
+
+    #[inline]
+    fn bind(&self, tape: &mut Tape<T>) {
+        self.1.bind(tape);
+        let value = tape.var((self.0).1, Tags::position());
+        tape.bound.push(value);
+    }
+}
+
+/// Labelled paren of `(tag, adjoint)` pairs for a paren of tags.
+#[doc(hidden)]
+pub trait Keyed<T> {
+    const LEN: usize;
+    type Output;
+    fn keyed(adjoints: &[T]) -> Self::Output;
+}
+
+impl<T> Keyed<T> for () {
+    const LEN: usize = 0;
+    type Output = ();
+
+    #[inline(always)]
+    fn keyed(_: &[T]) -> Self::Output {}
+}
+
+impl<T: Copy, K: Default, B: Keyed<T>> Keyed<T> for (K, B) {
+    const LEN: usize = B::LEN + 1;
+    type Output = ((K, T), B::Output);
+
+    #[inline]
+    fn keyed(adjoints: &[T]) -> Self::Output {
+        ((K::default(), adjoints[0]), B::keyed(&adjoints[1..]))
+    }
+}
+
+pub type KeyedOut<Tags, T> = <Tags as Keyed<T>>::Output;
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Evaluation of an expression on a [`Tape`], where the variables tagged with one of `Tags` are
+/// the first nodes of the tape in the same order.
+///
+/// [`Tape`]: struct.Tape.html
+pub trait EvalTape<Tags> {
+    type Value;
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value>;
+}
+
+impl<T: EvalTape<Tags>, Tags> EvalTape<Tags> for Expr<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        self.0.eval_tape(tape)
+    }
+}
+
+impl<T: Float, Tag, D, Tags: Position<Tag>> EvalTape<Tags> for Var<T, Tag, D> {
+    type Value = T;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<T>) -> Tracked<T> {
+        tape.var((*self).eval(), Tags::position())
+    }
+}
+
+impl<T: Float, Tag, D, Tags: Depth<Tag>> EvalTape<Tags> for Placeholder<T, Tag, D> {
+    type Value = T;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<T>) -> Tracked<T> {
+        tape.bound[tape.bound.len() - 1 - Tags::depth()]
+    }
+}
+
+impl<T: Float, Tags> EvalTape<Tags> for Const<T> {
+    type Value = T;
+
+    #[inline]
+    fn eval_tape(&self, _: &mut Tape<T>) -> Tracked<T> {
+        Tape::constant(self.0)
+    }
+}
+
+macro_rules! eval_tape_identity {
+    ($($name:ident => $value:ident),*) => {$(
+        impl<T: Float, Tags> EvalTape<Tags> for $name<T> {
+            type Value = T;
+
+            #[inline]
+            fn eval_tape(&self, _: &mut Tape<T>) -> Tracked<T> {
+                Tape::constant(T::$value)
+            }
+        }
+    )*};
+}
+
+eval_tape_identity!(Zero => ZERO, One => ONE);
+
+// the partial derivatives of unary nodes in terms of the operand `x` and the result `y`
+macro_rules! eval_tape_unary {
+    ($($name:ident(|$x:ident, $y:ident| $value:expr, $deriv:expr);)*) => {$(
+        impl<T: EvalTape<Tags>, Tags> EvalTape<Tags> for $name<T>
+        where
+            T::Value: Float,
+        {
+            type Value = T::Value;
+
+            #[inline]
+            fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+                let x = self.0.eval_tape(tape);
+                let $x = x.value;
+                let $y = $value;
+                tape.push1($y, x, $deriv)
+            }
+        }
+    )*};
+}
+
+eval_tape_unary!(
+    Neg(|x, _y| -x, -<Self::Value as Num>::ONE);
+    Sin(|x, _y| x.sin(), x.cos());
+    Cos(|x, _y| x.cos(), -x.sin());
+    Exp(|x, y| x.exp(), y);
+    Ln(|x, _y| x.ln(), <Self::Value as Num>::ONE / x);
+    Sqrt(|x, y| x.sqrt(), <Self::Value as Num>::ONE / (y + y));
+    Tanh(|x, y| x.tanh(), <Self::Value as Num>::ONE - y * y);
+);
+
+impl<T: EvalTape<Tags>, Tags> EvalTape<Tags> for Powi<T>
+where
+    T::Value: Float,
+{
+    type Value = T::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        let x = self.0.eval_tape(tape);
+        let (n, value) = (self.1, powi(x.value, self.1));
+        // n x^(n - 1) isn't finite at zero for n = 0
+        let dx = match n.checked_sub(1) {
+            _ if n == 0 => <Self::Value as Num>::ZERO,
+            Some(m) => <Self::Value as Float>::from_i64(n) * powi(x.value, m),
+            None => <Self::Value as Float>::from_i64(n) * (value / x.value),
+        };
+        tape.push1(value, x, dx)
+    }
+}
+
+impl<T: EvalTape<Tags>, N: Int, Tags> EvalTape<Tags> for Pow<T, N>
+where
+    T::Value: Float,
+{
+    type Value = T::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        let x = self.0.eval_tape(tape);
+        let dx = match N::VALUE {
+            0 => <Self::Value as Num>::ZERO,
+            n => <Self::Value as Float>::from_i32(n) * x.value.powi(n - 1),
+        };
+        tape.push1(x.value.powi(N::VALUE), x, dx)
+    }
+}
+
+impl<F, T: EvalTape<Tags>, Tags> EvalTape<Tags> for Call<F, T>
+where
+    F: Differentiable + Func<T::Value, Output = T::Value>,
+    F::Deriv: Func<T::Value, Output = T::Value>,
+    T::Value: Float,
+{
+    type Value = T::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        let x = self.1.eval_tape(tape);
+        tape.push1(F::call(x.value), x, F::Deriv::call(x.value))
+    }
+}
+
+// the partial derivatives of binary nodes in terms of the operands `a` and `b` and the result `y`
+macro_rules! eval_tape_binary {
+    ($($name:ident(|$a:ident, $b:ident, $y:ident| $value:expr, $da:expr, $db:expr);)*) => {$(
+        impl<L, R, Tags> EvalTape<Tags> for $name<L, R>
+        where
+            L: EvalTape<Tags>,
+            R: EvalTape<Tags, Value = L::Value>,
+            L::Value: Float,
+        {
+            type Value = L::Value;
+
+            #[inline]
+            fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+                let a = self.0.eval_tape(tape);
+                let b = self.1.eval_tape(tape);
+                let ($a, $b) = (a.value, b.value);
+                let $y = $value;
+                tape.push($y, [(a, $da), (b, $db)])
+            }
+        }
+    )*};
+}
+
+eval_tape_binary!(
+    Add(|a, b, _y| a + b, <Self::Value as Num>::ONE, <Self::Value as Num>::ONE);
+    Sub(|a, b, _y| a - b, <Self::Value as Num>::ONE, -<Self::Value as Num>::ONE);
+    Mul(|a, b, _y| a * b, b, a);
+    Div(|a, b, y| a / b, <Self::Value as Num>::ONE / b, -y / b);
+    Powf(|a, b, y| a.powf(b), b * a.powf(b - <Self::Value as Num>::ONE), y * a.ln());
+);
+
+// l % r = l - trunc(l / r) * r
+impl<L, R, Tags> EvalTape<Tags> for Rem<L, R>
+where
+    L: EvalTape<Tags>,
+    R: EvalTape<Tags, Value = L::Value>,
+    L::Value: Float + std::ops::Rem<Output = L::Value>,
+{
+    type Value = L::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        let a = self.0.eval_tape(tape);
+        let b = self.1.eval_tape(tape);
+        let y = a.value % b.value;
+        let trunc = (a.value - y) / b.value;
+        tape.push(y, [(a, <Self::Value as Num>::ONE), (b, -trunc)])
+    }
+}
+
+/// Evaluation of the condition of a [`Select`] on a [`Tape`]. The result isn't differentiable, so
+/// only the values of the operands are compared.
+///
+/// [`Select`]: ../cond/struct.Select.html
+/// [`Tape`]: struct.Tape.html
+pub trait EvalTapeCond<Tags> {
+    type Value;
+    fn eval_tape_cond(&self, tape: &mut Tape<Self::Value>) -> bool;
+}
+
+macro_rules! eval_tape_compare {
+    ($($name:ident, $trait:ident::$op:ident;)*) => {$(
+        impl<L, R, Tags> EvalTapeCond<Tags> for $name<L, R>
+        where
+            L: EvalTape<Tags>,
+            R: EvalTape<Tags, Value = L::Value>,
+            L::Value: $trait,
+        {
+            type Value = L::Value;
+
+            #[inline]
+            fn eval_tape_cond(&self, tape: &mut Tape<Self::Value>) -> bool {
+                let a = self.0.eval_tape(tape);
+                let b = self.1.eval_tape(tape);
+                a.value.$op(&b.value)
+            }
+        }
+    )*};
+}
+
+eval_tape_compare!(
+    Less, PartialOrd::lt;
+    LessEq, PartialOrd::le;
+    Greater, PartialOrd::gt;
+    GreaterEq, PartialOrd::ge;
+    Equal, PartialEq::eq;
+    NotEqual, PartialEq::ne;
+);
+
+impl<T: EvalTapeCond<Tags>, Tags> EvalTapeCond<Tags> for Expr<T> {
+    type Value = T::Value;
+
+    #[inline]
+    fn eval_tape_cond(&self, tape: &mut Tape<Self::Value>) -> bool {
+        self.0.eval_tape_cond(tape)
+    }
+}
+
+// only the chosen branch is recorded, so the other one gets no adjoints
+impl<C, A, B, Tags> EvalTape<Tags> for Select<C, A, B>
+where
+    C: EvalTapeCond<Tags, Value = A::Value>,
+    A: EvalTape<Tags>,
+    B: EvalTape<Tags, Value = A::Value>,
+{
+    type Value = A::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        if self.0.eval_tape_cond(tape) {
+            self.1.eval_tape(tape)
+        } else {
+            self.2.eval_tape(tape)
+        }
+    }
+}
+
+// the bound value is recorded once, so the adjoints of all its uses in the body add up in its node
+impl<Tag, V, B, Tags> EvalTape<Tags> for Let<Tag, V, B>
+where
+    V: EvalTape<Tags>,
+    B: EvalTape<Scope<Tag, Tags>, Value = V::Value>,
+{
+    type Value = V::Value;
+
+    #[inline]
+    fn eval_tape(&self, tape: &mut Tape<Self::Value>) -> Tracked<Self::Value> {
+        let value = self.1.eval_tape(tape);
+        tape.bound.push(value);
+        let y = self.2.eval_tape(tape);
+        tape.bound.pop();
+        y
+    }
+}
+
+impl<T> Expr<T> {
+    /// Evaluate the expression on a [`Tape`] and back-propagate the adjoints to get its value
+    /// and gradient in a single backward pass. The gradient is a labelled paren with the
+    /// derivative for each tag in `tags`.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::paren::Paren;
+    /// use typing::expr::var::var;
+    /// use typing::tags::{X, Y, Z};
+    ///
+    /// let x = var(2.0, X);
+    /// let y = var(4.0, Y);
+    /// let f = x * x * y - x / y;
+    ///
+    /// let (value, grad) = f.grad_reverse(paren!(X, Y, Z));
+    /// assert_eq!(value, 15.5);
+    /// assert_eq!(*grad.lookup(X), 15.75);
+    /// assert_eq!(*grad.lookup(Y), 4.125);
+    /// assert_eq!(*grad.lookup(Z), 0.0);
+    /// ```
+    ///
+    /// [`Tape`]: tape/struct.Tape.html
+    pub fn grad_reverse<Tags>(&self, _: Tags) -> (T::Value, KeyedOut<Tags, T::Value>)
+    where
+        T: EvalTape<Tags>,
+        T::Value: Float,
+        Tags: Keyed<T::Value>,
+    {
+        let mut tape = Tape::new(Tags::LEN);
+        let output = self.0.eval_tape(&mut tape);
+        let adjoints = tape.adjoints(output);
+        (output.value, Tags::keyed(&adjoints))
+    }
+
+    /// Like [`grad_reverse`], but with the values of the placeholders taken from an environment,
+    /// given as a labelled paren of `(tag, value)` pairs like for [`eval_with`]. Placeholders
+    /// tagged with one of `tags` are differentiated like variables.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::paren::Paren;
+    /// use typing::expr::var::{placeholder, var};
+    /// use typing::tags::{X, Y};
+    ///
+    /// let x = placeholder::<f64, _>(X);
+    /// let y = var(4.0, Y);
+    /// let f = x * x * y - x / y;
+    ///
+    /// let (value, grad) = f.grad_reverse_with(&paren!((X, 2.0)), paren!(X, Y));
+    /// assert_eq!(value, 15.5);
+    /// assert_eq!(*grad.lookup(X), 15.75);
+    /// assert_eq!(*grad.lookup(Y), 4.125);
+    /// ```
+    ///
+    /// [`grad_reverse`]: #method.grad_reverse
+    /// [`eval_with`]: #method.eval_with
+    pub fn grad_reverse_with<Env, Tags, V>(&self, env: &Env, _: Tags) -> (V, KeyedOut<Tags, V>)
+    where
+        Env: TapeEnv<V, Tags>,
+        T: EvalTape<Env::Scope, Value = V>,
+        V: Float,
+        Tags: Keyed<V>,
+    {
+        let mut tape = Tape::new(Tags::LEN);
+        env.bind(&mut tape);
+        let output = self.0.eval_tape(&mut tape);
+        let adjoints = tape.adjoints(output);
+        (output.value, Tags::keyed(&adjoints))
+    }
+}
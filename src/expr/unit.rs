@@ -0,0 +1,412 @@
+//! Physical quantities with their dimension checked at compile time. A dimension is a paren of
+//! [type-level integers](../../int/index.html) with the exponents of the SI base dimensions
+//! length, mass, time, electric current, temperature, amount of substance and luminous intensity.
+//!
+//! Using quantities as the values of variables created with [`Quantity::var`], sums of
+//! expressions require equal dimensions, while products and quotients add and subtract their
+//! exponents. The derivative of a variable with respect to itself is dimensionless, so
+//! derivatives are divided by the unit of the variable.
+//! # Examples
+//! ```
+//! use typing::expr::unit::{Acceleration, DimProduct, DimQuotient, Length, Quantity};
+//! use typing::expr::unit::{Dimensionless, Time, Velocity};
+//! use typing::int::{P2, Z0};
+//! use typing::tags::{S, T, V};
+//!
+//! let s = Quantity::<f64, Length>::new(100.0).var(S);
+//! let t = Quantity::<_, Time>::new(8.0).var(T);
+//! let v0 = Quantity::<_, Velocity>::new(5.0).var(V);
+//!
+//! // the acceleration needed to travel `s` in time `t` starting at speed `v0`
+//! let a = (s - v0 * t) * 2.0 / (t * t);
+//! assert_eq!(a.eval().value(), 1.875);
+//!
+//! // da/dt has the unit of a divided by time
+//! let jerk: Quantity<f64, DimQuotient<Acceleration, Time>> = a.deriv(T).eval();
+//! assert_eq!(jerk.value(), -0.625);
+//!
+//! // integer powers multiply the exponents
+//! let area: Quantity<f64, DimProduct<Length, Length>> = s.pow::<P2>().eval();
+//! assert_eq!(area.value(), 10000.0);
+//! let one: Quantity<f64, Dimensionless> = s.pow::<Z0>().reduce().eval();
+//! assert_eq!(one.value(), 1.0);
+//! ```
+//!
+//! The derivative of a variable with respect to a different tag is zero in the unit of the
+//! variable itself rather than divided by the unit of the other tag, which a single variable
+//! doesn't know. As zeros are removed from sums and products, this only shows when such a
+//! derivative is evaluated on its own:
+//! ```
+//! use typing::expr::unit::{Length, Quantity, Time};
+//! use typing::tags::{S, T};
+//!
+//! let s = Quantity::<f64, Length>::new(100.0).var(S);
+//! let t = Quantity::<f64, Time>::new(8.0).var(T);
+//!
+//! let _: Quantity<f64, Length> = s.deriv(T).eval();
+//! let _: Quantity<f64, Time> = (s * t).deriv(S).eval();
+//! ```
+//!
+//! Adding quantities of different dimensions doesn't compile:
+//! ```compile_fail
+//! use typing::expr::unit::{Length, Quantity, Time};
+//! use typing::tags::{S, T};
+//!
+//! let s = Quantity::<f64, Length>::new(100.0).var(S);
+//! let t = Quantity::<f64, Time>::new(8.0).var(T);
+//! let _ = (s + t).eval();
+//! ```
+//!
+//! [`Quantity::var`]: struct.Quantity.html#method.var
+use crate::boolean::{And, Bool, BoolAnd, False, True};
+use crate::expr::constant::Scalar;
+use crate::expr::elementary::{pow_by_squaring, IntPow};
+use crate::expr::var::{Placeholder, Var};
+use crate::expr::{Expr, One, Zero};
+use crate::int::{
+    Int, IntAdd, IntIsZero, IntMul, IntSub, IsZero, Minus, Plus, Times, N1, N2, P1, Z0,
+};
+use crate::num::{Float, Num};
+use crate::Paren;
+use std::cmp::Ordering;
+use std::fmt::{Debug, Formatter};
+use std::marker::PhantomData;
+
+pub type Dimensionless = Paren!(Z0, Z0, Z0, Z0, Z0, Z0, Z0);
+pub type Length = Paren!(P1, Z0, Z0, Z0, Z0, Z0, Z0);
+pub type Mass = Paren!(Z0, P1, Z0, Z0, Z0, Z0, Z0);
+pub type Time = Paren!(Z0, Z0, P1, Z0, Z0, Z0, Z0);
+pub type Current = Paren!(Z0, Z0, Z0, P1, Z0, Z0, Z0);
+pub type Temperature = Paren!(Z0, Z0, Z0, Z0, P1, Z0, Z0);
+pub type Amount = Paren!(Z0, Z0, Z0, Z0, Z0, P1, Z0);
+pub type Luminosity = Paren!(Z0, Z0, Z0, Z0, Z0, Z0, P1);
+
+pub type Velocity = Paren!(P1, Z0, N1, Z0, Z0, Z0, Z0);
+pub type Acceleration = Paren!(P1, Z0, N2, Z0, Z0, Z0, Z0);
+pub type Force = Paren!(P1, P1, N2, Z0, Z0, Z0, Z0);
+
+/// Dimension of the product of quantities, adding the exponents.
+pub trait DimMul<R> {
+    type Output;
+}
+
+pub type DimProduct<L, R> = <L as DimMul<R>>::Output;
+
+impl DimMul<()> for () {
+    type Output = ();
+}
+
+impl<A: IntAdd<C>, B: DimMul<D>, C, D> DimMul<(C, D)> for (A, B) {
+    type Output = (Plus<A, C>, DimProduct<B, D>);
+}
+
+/// Dimension of the quotient of quantities, subtracting the exponents.
+pub trait DimDiv<R> {
+    type Output;
+}
+
+pub type DimQuotient<L, R> = <L as DimDiv<R>>::Output;
+
+impl DimDiv<()> for () {
+    type Output = ();
+}
+
+impl<A: IntSub<C>, B: DimDiv<D>, C, D> DimDiv<(C, D)> for (A, B) {
+    type Output = (Minus<A, C>, DimQuotient<B, D>);
+}
+
+/// Dimension of an integer power of a quantity, multiplying the exponents by `N`.
+pub trait DimPow<N> {
+    type Output;
+}
+
+pub type DimPowOut<D, N> = <D as DimPow<N>>::Output;
+
+impl<N> DimPow<N> for () {
+    type Output = ();
+}
+
+impl<A: IntMul<N>, B: DimPow<N>, N> DimPow<N> for (A, B) {
+    type Output = (Times<A, N>, DimPowOut<B, N>);
+}
+
+/// Whether all exponents of a dimension are zero.
+pub trait IsDimensionless {
+    type Output: Bool;
+}
+
+impl IsDimensionless for () {
+    type Output = True;
+}
+
+impl<A: IntIsZero, B: IsDimensionless> IsDimensionless for (A, B)
+where
+    IsZero<A>: BoolAnd<B::Output>,
+{
+    type Output = And<IsZero<A>, B::Output>;
+}
+
+/// A value of type `T` with dimension `D`.
+pub struct Quantity<T, D>(T, PhantomData<fn() -> D>);
+
+impl<T, D> Quantity<T, D> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self(value, PhantomData)
+    }
+
+    #[inline]
+    pub fn value(self) -> T {
+        self.0
+    }
+
+    /// A variable with this quantity as its value, whose derivatives are divided by the unit of
+    /// the quantity.
+    #[inline]
+    pub fn var<Tag>(self, _: Tag) -> Expr<Var<Self, Tag, D>> {
+        Expr(Var::new(self))
+    }
+
+    /// A placeholder variable for quantities of this type, see [`placeholder`].
+    ///
+    /// [`placeholder`]: ../var/fn.placeholder.html
+    #[inline]
+    pub fn placeholder<Tag>(_: Tag) -> Expr<Placeholder<Self, Tag, D>> {
+        Expr(Placeholder::new())
+    }
+}
+
+impl<T: Debug, D> Debug for Quantity<T, D> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_tuple("Quantity").field(&self.0).finish()
+    }
+}
+
+impl<T: Clone, D> Clone for Quantity<T, D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.0.clone())
+    }
+}
+
+impl<T: Copy, D> Copy for Quantity<T, D> {}
+
+impl<T: Default, D> Default for Quantity<T, D> {
+    #[inline]
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: PartialEq, D> PartialEq for Quantity<T, D> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: PartialOrd, D> PartialOrd for Quantity<T, D> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.0.partial_cmp(&other.0)
+    }
+}
+
+impl<T: std::ops::Neg, D> std::ops::Neg for Quantity<T, D> {
+    type Output = Quantity<T::Output, D>;
+
+    #[inline]
+    fn neg(self) -> Self::Output {
+        Quantity::new(-self.0)
+    }
+}
+
+macro_rules! quantity_binop {
+    ($($name:ident, $method:ident, $dim:ident;)*) => {$(
+        impl<T, U, D, E> std::ops::$name<Quantity<U, E>> for Quantity<T, D>
+        where
+            T: std::ops::$name<U>,
+            D: $dim<E>,
+        {
+            type Output = Quantity<T::Output, D::Output>;
+
+            #[inline]
+            fn $method(self, rhs: Quantity<U, E>) -> Self::Output {
+                Quantity::new(std::ops::$name::$method(self.0, rhs.0))
+            }
+        }
+    )*};
+}
+
+quantity_binop!(
+    Add, add, SameDim;
+    Sub, sub, SameDim;
+    Rem, rem, SameDim;
+    Mul, mul, DimMul;
+    Div, div, DimDiv;
+);
+
+/// Dimension of sums, which requires the dimensions to be equal.
+#[doc(hidden)]
+pub trait SameDim<R> {
+    type Output;
+}
+
+impl<D> SameDim<D> for D {
+    type Output = D;
+}
+
+// products with plain scalars, which keep the dimension of the quantity. note that the scalar
+// types on the left hand side need to be listed explicitly due to orphan rules
+macro_rules! quantity_scalar_binop {
+    ($($t:ty)*) => {
+        impl<T: std::ops::Mul<S>, S: Scalar, D> std::ops::Mul<S> for Quantity<T, D> {
+            type Output = Quantity<T::Output, D>;
+
+            #[inline]
+            fn mul(self, rhs: S) -> Self::Output {
+                Quantity::new(self.0 * rhs)
+            }
+        }
+
+        impl<T: std::ops::Div<S>, S: Scalar, D> std::ops::Div<S> for Quantity<T, D> {
+            type Output = Quantity<T::Output, D>;
+
+            #[inline]
+            fn div(self, rhs: S) -> Self::Output {
+                Quantity::new(self.0 / rhs)
+            }
+        }
+
+        $(
+            impl<T, D> std::ops::Mul<Quantity<T, D>> for $t
+            where
+                $t: std::ops::Mul<T>,
+            {
+                type Output = Quantity<<$t as std::ops::Mul<T>>::Output, D>;
+
+                #[inline]
+                fn mul(self, rhs: Quantity<T, D>) -> Self::Output {
+                    Quantity::new(self * rhs.0)
+                }
+            }
+
+            impl<T, D> std::ops::Div<Quantity<T, D>> for $t
+            where
+                $t: std::ops::Div<T>,
+                Dimensionless: DimDiv<D>,
+            {
+                type Output =
+                    Quantity<<$t as std::ops::Div<T>>::Output, DimQuotient<Dimensionless, D>>;
+
+                #[inline]
+                fn div(self, rhs: Quantity<T, D>) -> Self::Output {
+                    Quantity::new(self / rhs.0)
+                }
+            }
+        )*
+    };
+}
+
+quantity_scalar_binop!(f32 f64 i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+impl<T, D: DimPow<N>, N: Int> IntPow<N> for Quantity<T, D>
+where
+    T: Num + std::ops::Mul<Output = T> + std::ops::Div<Output = T>,
+{
+    type Output = Quantity<T, DimPowOut<D, N>>;
+
+    #[inline(always)]
+    fn int_pow(self) -> Self::Output {
+        Quantity::new(pow_by_squaring(self.0, N::VALUE.into()))
+    }
+}
+
+impl<T: Num, D> Num for Quantity<T, D> {
+    const ZERO: Self = Quantity(T::ZERO, PhantomData);
+    const ONE: Self = Quantity(T::ONE, PhantomData);
+}
+
+macro_rules! float_unary {
+    ($($method:ident)*) => {$(
+        #[inline]
+        fn $method(self) -> Self {
+            Self::new(self.0.$method())
+        }
+    )*};
+}
+
+// only dimensionless quantities can be used in the elementary functions
+impl<T: Float> Float for Quantity<T, Dimensionless> {
+    #[inline]
+    fn from_i32(n: i32) -> Self {
+        Self::new(T::from_i32(n))
+    }
+
+    #[inline]
+    fn from_i64(n: i64) -> Self {
+        Self::new(T::from_i64(n))
+    }
+
+    #[inline]
+    fn powi(self, n: i32) -> Self {
+        Self::new(self.0.powi(n))
+    }
+
+    #[inline]
+    fn powf(self, n: Self) -> Self {
+        Self::new(self.0.powf(n.0))
+    }
+
+    float_unary!(sin cos exp ln sqrt tanh);
+}
+
+/// The dimensionless counterpart of a value type, which is the type of the derivative of a
+/// variable with respect to itself.
+pub trait Unitless {
+    type Output;
+}
+
+pub type UnitlessOut<T> = <T as Unitless>::Output;
+
+impl<T: Scalar> Unitless for T {
+    type Output = T;
+}
+
+impl<T, D> Unitless for Quantity<T, D> {
+    type Output = Quantity<T, Dimensionless>;
+}
+
+/// The derivative of a variable of type `T` with dimension `Self`, which is one if `Same` is true
+/// and zero otherwise. Variables of plain values are dimensionless, so their derivatives have the
+/// type of the variable, while the derivative of a quantity with respect to itself is
+/// dimensionless.
+pub trait VarDeriv<T, Same> {
+    type Output: Default;
+}
+
+pub type VarDerivOut<D, T, Same> = <D as VarDeriv<T, Same>>::Output;
+
+#[doc(hidden)]
+pub trait VarDeriv2<T, Same, Marker> {
+    type Output: Default;
+}
+
+impl<T, Same, D: IsDimensionless> VarDeriv<T, Same> for D
+where
+    D: VarDeriv2<T, Same, <D as IsDimensionless>::Output>,
+{
+    type Output = <D as VarDeriv2<T, Same, <D as IsDimensionless>::Output>>::Output;
+}
+
+impl<T, D> VarDeriv2<T, True, True> for D {
+    type Output = One<T>;
+}
+
+impl<D, V> VarDeriv2<Quantity<V, D>, True, False> for D {
+    type Output = One<Quantity<V, Dimensionless>>;
+}
+
+impl<T, D, Marker> VarDeriv2<T, False, Marker> for D {
+    type Output = Zero<T>;
+}
@@ -1,22 +1,31 @@
-use crate::boolean::{BoolIfElse, IfElse};
+use crate::boolean::{False, True};
 use crate::expr::deriv::Deriv;
-use crate::expr::{Expr, Expression, One, Reduce, Zero};
-use crate::ident::TypeEq;
+use crate::expr::env::EvalWith;
+use crate::expr::subst::{Subst, SubstVar};
+use crate::expr::unit::{Dimensionless, VarDeriv, VarDerivOut};
+use crate::expr::{Expr, Expression, Reduce};
+use crate::ident::{IsEq, TypeEq};
+use crate::paren::ops::Lookup;
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 
-pub struct Var<T, Tag> {
+/// A variable of type `T` with a value, tagged with `Tag`. The dimension `D` is only used for the
+/// type of derivatives: variables of plain values are dimensionless, while [`Quantity::var`]
+/// creates variables with the dimension of the quantity.
+///
+/// [`Quantity::var`]: ../unit/struct.Quantity.html#method.var
+pub struct Var<T, Tag, D = Dimensionless> {
     value: T,
-    _tag: PhantomData<*const Tag>,
+    _tag: PhantomData<*const (Tag, D)>,
 }
 
-impl<T: Debug, Tag: Default + Debug> Debug for Var<T, Tag> {
+impl<T: Debug, Tag: Default + Debug, D> Debug for Var<T, Tag, D> {
     fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
         write!(f, "{:?}({:?})", Tag::default(), self.value)
     }
 }
 
-impl<T: Clone, Tag> Clone for Var<T, Tag> {
+impl<T: Clone, Tag, D> Clone for Var<T, Tag, D> {
     #[inline]
     fn clone(&self) -> Self {
         Self {
@@ -26,9 +35,9 @@ impl<T: Clone, Tag> Clone for Var<T, Tag> {
     }
 }
 
-impl<T: Copy, Tag> Copy for Var<T, Tag> {}
+impl<T: Copy, Tag, D> Copy for Var<T, Tag, D> {}
 
-impl<T: Default, Tag> Default for Var<T, Tag> {
+impl<T: Default, Tag, D> Default for Var<T, Tag, D> {
     #[inline]
     fn default() -> Self {
         Self {
@@ -38,7 +47,7 @@ impl<T: Default, Tag> Default for Var<T, Tag> {
     }
 }
 
-impl<T, Tag> Var<T, Tag> {
+impl<T, Tag, D> Var<T, Tag, D> {
     #[inline]
     pub fn new(value: T) -> Self {
         Self {
@@ -48,7 +57,7 @@ impl<T, Tag> Var<T, Tag> {
     }
 }
 
-impl<T, Tag> Expression for Var<T, Tag> {
+impl<T, Tag, D> Expression for Var<T, Tag, D> {
     type Output = T;
 
     #[inline]
@@ -57,21 +66,60 @@ impl<T, Tag> Expression for Var<T, Tag> {
     }
 }
 
-impl<T, TagA, TagB, Same> Deriv<TagB> for Var<T, TagA>
+impl<T: Clone, Tag, Env, D> EvalWith<Env> for Var<T, Tag, D> {
+    type Output = T;
+
+    #[inline]
+    fn eval_with(&self, _: &Env) -> Self::Output {
+        self.value.clone()
+    }
+}
+
+impl<T, TagA, TagB, R, D> Subst<TagB, R> for Var<T, TagA, D>
+where
+    TagA: TypeEq<TagB>,
+    Self: SubstVar<R, IsEq<TagA, TagB>>,
+{
+    type Output = <Self as SubstVar<R, IsEq<TagA, TagB>>>::Output;
+
+    #[inline(always)]
+    fn subst(self, replacement: &R) -> Self::Output {
+        self.subst_var(replacement)
+    }
+}
+
+impl<T, Tag, R: Clone, D> SubstVar<R, True> for Var<T, Tag, D> {
+    type Output = R;
+
+    #[inline]
+    fn subst_var(self, replacement: &R) -> Self::Output {
+        replacement.clone()
+    }
+}
+
+impl<T, Tag, R, D> SubstVar<R, False> for Var<T, Tag, D> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn subst_var(self, _: &R) -> Self::Output {
+        self
+    }
+}
+
+impl<T, TagA, TagB, D> Deriv<TagB> for Var<T, TagA, D>
 where
-    TagA: TypeEq<TagB, Output = Same>,
-    Same: BoolIfElse<One, Zero>,
-    IfElse<Same, One, Zero>: Default,
+    TagA: TypeEq<TagB>,
+    D: VarDeriv<T, IsEq<TagA, TagB>>,
 {
-    type Output = IfElse<Same, One, Zero>;
+    type Output = VarDerivOut<D, T, IsEq<TagA, TagB>>;
 
     #[inline]
     fn deriv(&self) -> Self::Output {
-        IfElse::<Same, One, Zero>::default()
+        Default::default()
     }
 }
 
-impl<T, Tag> Reduce for Var<T, Tag> {
+impl<T, Tag, D> Reduce for Var<T, Tag, D> {
     type Output = Self;
     #[inline(always)]
     fn reduce(self) -> Self::Output {
@@ -83,3 +131,136 @@ impl<T, Tag> Reduce for Var<T, Tag> {
 pub fn var<T, Tag>(x: T, _: Tag) -> Expr<Var<T, Tag>> {
     Expr(Var::new(x))
 }
+
+////////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A variable of type `T` without a value, which has to be bound by an environment when
+/// evaluating the expression with [`EvalWith`]. The dimension `D` is used like that of [`Var`].
+///
+/// [`EvalWith`]: ../env/trait.EvalWith.html
+/// [`Var`]: struct.Var.html
+pub struct Placeholder<T, Tag, D = Dimensionless>(PhantomData<*const (T, Tag, D)>);
+
+impl<T, Tag: Default + Debug, D> Debug for Placeholder<T, Tag, D> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), std::fmt::Error> {
+        write!(f, "{:?}", Tag::default())
+    }
+}
+
+impl<T, Tag, D> Clone for Placeholder<T, Tag, D> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, Tag, D> Copy for Placeholder<T, Tag, D> {}
+
+impl<T, Tag, D> Default for Placeholder<T, Tag, D> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Tag, D> Placeholder<T, Tag, D> {
+    #[inline]
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: Clone, Tag, Env, D> EvalWith<Env> for Placeholder<T, Tag, D>
+where
+    Env: Lookup<Tag, Output = T>,
+{
+    type Output = T;
+
+    #[inline]
+    fn eval_with(&self, env: &Env) -> Self::Output {
+        env.lookup().clone()
+    }
+}
+
+impl<T, TagA, TagB, R, D> Subst<TagB, R> for Placeholder<T, TagA, D>
+where
+    TagA: TypeEq<TagB>,
+    Self: SubstVar<R, IsEq<TagA, TagB>>,
+{
+    type Output = <Self as SubstVar<R, IsEq<TagA, TagB>>>::Output;
+
+    #[inline(always)]
+    fn subst(self, replacement: &R) -> Self::Output {
+        self.subst_var(replacement)
+    }
+}
+
+impl<T, Tag, R: Clone, D> SubstVar<R, True> for Placeholder<T, Tag, D> {
+    type Output = R;
+
+    #[inline]
+    fn subst_var(self, replacement: &R) -> Self::Output {
+        replacement.clone()
+    }
+}
+
+impl<T, Tag, R, D> SubstVar<R, False> for Placeholder<T, Tag, D> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn subst_var(self, _: &R) -> Self::Output {
+        self
+    }
+}
+
+impl<T, TagA, TagB, D> Deriv<TagB> for Placeholder<T, TagA, D>
+where
+    TagA: TypeEq<TagB>,
+    D: VarDeriv<T, IsEq<TagA, TagB>>,
+{
+    type Output = VarDerivOut<D, T, IsEq<TagA, TagB>>;
+
+    #[inline]
+    fn deriv(&self) -> Self::Output {
+        Default::default()
+    }
+}
+
+impl<T, Tag, D> Reduce for Placeholder<T, Tag, D> {
+    type Output = Self;
+    #[inline(always)]
+    fn reduce(self) -> Self::Output {
+        self
+    }
+}
+
+/// Create a placeholder variable of type `T` for use with [`EvalWith`].
+/// # Examples
+/// ```
+/// use typing::paren;
+/// use typing::expr::var::placeholder;
+/// use typing::tags::{X, Y};
+///
+/// let x = placeholder::<f64, _>(X);
+/// let y = placeholder::<f64, _>(Y);
+/// let f = x * x * y;
+///
+/// assert_eq!(f.eval_with(&paren!((X, 2.0), (Y, 3.0))), 12.0);
+/// assert_eq!(f.eval_with(&paren!((Y, -1.0), (X, 0.5))), -0.25);
+/// assert_eq!(f.deriv(X).eval_with(&paren!((X, 2.0), (Y, 3.0))), 12.0);
+/// ```
+///
+/// Evaluating without binding every placeholder is a compile-time error.
+/// ```compile_fail
+/// use typing::paren;
+/// use typing::expr::var::placeholder;
+/// use typing::tags::{X, Y};
+///
+/// let f = placeholder::<f64, _>(X) * placeholder::<f64, _>(Y);
+/// f.eval_with(&paren!((X, 2.0)));
+/// ```
+/// [`EvalWith`]: ../env/trait.EvalWith.html
+#[inline]
+pub fn placeholder<T, Tag>(_: Tag) -> Expr<Placeholder<T, Tag>> {
+    Expr(Placeholder::new())
+}
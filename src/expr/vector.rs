@@ -0,0 +1,472 @@
+use crate::expr::call::Call;
+use crate::expr::cond::{Equal, Greater, GreaterEq, Less, LessEq, NotEqual, Select};
+use crate::expr::constant::Const;
+use crate::expr::elementary::{powi, Cos, Exp, Ln, Pow, Powf, Powi, Sin, Sqrt, Tanh};
+use crate::expr::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Neg, Not, Rem, Shl, Shr, Sub};
+use crate::expr::share::{Let, ValueOf, ValueType};
+use crate::expr::var::Placeholder;
+use crate::expr::Expr;
+use crate::func::Func;
+use crate::int::Int;
+use crate::num::Float;
+use crate::paren::ops::Lookup;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Error when the operands of an element-wise expression, or the output it's evaluated into, have
+/// different lengths.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LengthMismatch {
+    pub expected: usize,
+    pub found: usize,
+}
+
+impl Display for LengthMismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "expected length {}, found {}", self.expected, self.found)
+    }
+}
+
+impl Error for LengthMismatch {}
+
+/// Common length of two operands, where `None` is a broadcast scalar.
+#[inline]
+fn common_length(l: Option<usize>, r: Option<usize>) -> Result<Option<usize>, LengthMismatch> {
+    match (l, r) {
+        (Some(expected), Some(found)) if expected != found => {
+            Err(LengthMismatch { expected, found })
+        }
+        (l, r) => Ok(l.or(r)),
+    }
+}
+
+/// Lazy element-wise evaluation of an expression over vectors. Constants are broadcast to every
+/// element, and so are the values bound to placeholders in the environment `Env`, which is a
+/// labelled paren like for [`EvalWith`].
+///
+/// [`EvalWith`]: ../env/trait.EvalWith.html
+pub trait Elementwise<Env = ()> {
+    type Item;
+
+    /// The number of elements, or `None` if the expression doesn't contain any vector.
+    fn length(&self) -> Result<Option<usize>, LengthMismatch>;
+
+    /// The element at index `i`, which must be less than the length.
+    fn at(&self, i: usize, env: &Env) -> Self::Item;
+}
+
+/// A borrowed slice in an element-wise expression.
+#[derive(Copy, Clone, Debug)]
+pub struct Slice<'a, T>(pub &'a [T]);
+
+/// Create an element-wise expression from a slice, `Vec` or array.
+#[inline]
+pub fn vector<'a, T: 'a, S: AsRef<[T]> + ?Sized>(values: &'a S) -> Expr<Slice<'a, T>> {
+    Expr(Slice(values.as_ref()))
+}
+
+impl<'a, T: Clone, Env> Elementwise<Env> for Slice<'a, T> {
+    type Item = T;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        Ok(Some(self.0.len()))
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, _: &Env) -> Self::Item {
+        self.0[i].clone()
+    }
+}
+
+impl<'a, T> ValueType for Slice<'a, T> {
+    type Output = T;
+}
+
+impl<T: Clone, Env> Elementwise<Env> for Const<T> {
+    type Item = T;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        Ok(None)
+    }
+
+    #[inline(always)]
+    fn at(&self, _: usize, _: &Env) -> Self::Item {
+        self.0.clone()
+    }
+}
+
+impl<T, Tag, D, Env> Elementwise<Env> for Placeholder<T, Tag, D>
+where
+    T: Clone,
+    Env: Lookup<Tag, Output = T>,
+{
+    type Item = T;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        Ok(None)
+    }
+
+    #[inline(always)]
+    fn at(&self, _: usize, env: &Env) -> Self::Item {
+        env.lookup().clone()
+    }
+}
+
+// the bound value is computed once for each element and looked up by the placeholders in the body
+impl<Tag, V, B, Env> Elementwise<Env> for Let<Tag, V, B>
+where
+    Tag: Clone,
+    V: Elementwise<Env>,
+    B: ValueType + for<'a> Elementwise<((Tag, V::Item), &'a Env), Item = ValueOf<B>>,
+{
+    type Item = ValueOf<B>;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        let body = <B as Elementwise<((Tag, V::Item), &Env)>>::length(&self.2)?;
+        common_length(self.1.length()?, body)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        self.2.at(i, &((self.0.clone(), self.1.at(i, env)), env))
+    }
+}
+
+macro_rules! elementwise_unop {
+    ($($name:ident, $method:ident;)*) => {$(
+        impl<T, Env> Elementwise<Env> for $name<T>
+        where
+            T: Elementwise<Env>,
+            T::Item: ::std::ops::$name,
+        {
+            type Item = <T::Item as ::std::ops::$name>::Output;
+
+            #[inline]
+            fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+                self.0.length()
+            }
+
+            #[inline(always)]
+            fn at(&self, i: usize, env: &Env) -> Self::Item {
+                ::std::ops::$name::$method(self.0.at(i, env))
+            }
+        }
+    )*};
+}
+
+elementwise_unop!(
+    Neg, neg;
+    Not, not;
+);
+
+macro_rules! elementwise_binop {
+    ($($name:ident, $method:ident;)*) => {$(
+        impl<L, R, Env> Elementwise<Env> for $name<L, R>
+        where
+            L: Elementwise<Env>,
+            R: Elementwise<Env>,
+            L::Item: ::std::ops::$name<R::Item>,
+        {
+            type Item = <L::Item as ::std::ops::$name<R::Item>>::Output;
+
+            #[inline]
+            fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+                common_length(self.0.length()?, self.1.length()?)
+            }
+
+            #[inline(always)]
+            fn at(&self, i: usize, env: &Env) -> Self::Item {
+                ::std::ops::$name::$method(self.0.at(i, env), self.1.at(i, env))
+            }
+        }
+    )*};
+}
+
+elementwise_binop!(
+    Add, add;
+    BitAnd, bitand;
+    BitOr, bitor;
+    BitXor, bitxor;
+    Div, div;
+    Mul, mul;
+    Rem, rem;
+    Shl, shl;
+    Shr, shr;
+    Sub, sub;
+);
+
+macro_rules! elementwise_elementary {
+    ($($name:ident, $method:ident;)*) => {$(
+        impl<T, Env> Elementwise<Env> for $name<T>
+        where
+            T: Elementwise<Env>,
+            T::Item: Float,
+        {
+            type Item = T::Item;
+
+            #[inline]
+            fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+                self.0.length()
+            }
+
+            #[inline(always)]
+            fn at(&self, i: usize, env: &Env) -> Self::Item {
+                Float::$method(self.0.at(i, env))
+            }
+        }
+    )*};
+}
+
+elementwise_elementary!(
+    Sin, sin;
+    Cos, cos;
+    Exp, exp;
+    Ln, ln;
+    Sqrt, sqrt;
+    Tanh, tanh;
+);
+
+impl<T, Env> Elementwise<Env> for Powi<T>
+where
+    T: Elementwise<Env>,
+    T::Item: Float,
+{
+    type Item = T::Item;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        self.0.length()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        powi(self.0.at(i, env), self.1)
+    }
+}
+
+impl<T, N: Int, Env> Elementwise<Env> for Pow<T, N>
+where
+    T: Elementwise<Env>,
+    T::Item: Float,
+{
+    type Item = T::Item;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        self.0.length()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        self.0.at(i, env).powi(N::VALUE)
+    }
+}
+
+impl<F, T, Env> Elementwise<Env> for Call<F, T>
+where
+    F: Func<T::Item>,
+    T: Elementwise<Env>,
+{
+    type Item = F::Output;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        self.1.length()
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        F::call(self.1.at(i, env))
+    }
+}
+
+impl<L, R, Env> Elementwise<Env> for Powf<L, R>
+where
+    L: Elementwise<Env>,
+    R: Elementwise<Env, Item = L::Item>,
+    L::Item: Float,
+{
+    type Item = L::Item;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        common_length(self.0.length()?, self.1.length()?)
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        self.0.at(i, env).powf(self.1.at(i, env))
+    }
+}
+
+macro_rules! elementwise_compare {
+    ($($name:ident, $trait:ident::$op:ident;)*) => {$(
+        impl<L, R, Env> Elementwise<Env> for $name<L, R>
+        where
+            L: Elementwise<Env>,
+            R: Elementwise<Env>,
+            L::Item: $trait<R::Item>,
+        {
+            type Item = bool;
+
+            #[inline]
+            fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+                common_length(self.0.length()?, self.1.length()?)
+            }
+
+            #[inline(always)]
+            fn at(&self, i: usize, env: &Env) -> Self::Item {
+                self.0.at(i, env).$op(&self.1.at(i, env))
+            }
+        }
+    )*};
+}
+
+elementwise_compare!(
+    Less, PartialOrd::lt;
+    LessEq, PartialOrd::le;
+    Greater, PartialOrd::gt;
+    GreaterEq, PartialOrd::ge;
+    Equal, PartialEq::eq;
+    NotEqual, PartialEq::ne;
+);
+
+// the choice is made for each element, so only the chosen element of either branch is computed
+impl<C, A, B, Env> Elementwise<Env> for Select<C, A, B>
+where
+    C: Elementwise<Env>,
+    C::Item: Into<bool>,
+    A: Elementwise<Env>,
+    B: Elementwise<Env, Item = A::Item>,
+{
+    type Item = A::Item;
+
+    #[inline]
+    fn length(&self) -> Result<Option<usize>, LengthMismatch> {
+        common_length(
+            self.0.length()?,
+            common_length(self.1.length()?, self.2.length()?)?,
+        )
+    }
+
+    #[inline(always)]
+    fn at(&self, i: usize, env: &Env) -> Self::Item {
+        if self.0.at(i, env).into() {
+            self.1.at(i, env)
+        } else {
+            self.2.at(i, env)
+        }
+    }
+}
+
+impl<T> Expr<T> {
+    /// Evaluate an element-wise expression into `out` in a single loop, without allocating any
+    /// temporaries for the intermediate results.
+    /// # Examples
+    /// ```
+    /// use typing::expr::vector::{vector, LengthMismatch};
+    ///
+    /// let a = [1.0, 2.0, 3.0];
+    /// let b = vec![4.0, 5.0, 6.0];
+    /// let c = &[0.5, 0.25, 2.0][..];
+    /// let e = vector(&a) + vector(&b) * vector(c) - 1.0;
+    ///
+    /// let mut out = [0.0; 3];
+    /// e.eval_into(&mut out).unwrap();
+    /// assert_eq!(out, [2.0, 2.25, 14.0]);
+    ///
+    /// let mut short = [0.0; 2];
+    /// assert_eq!(
+    ///     e.eval_into(&mut short),
+    ///     Err(LengthMismatch { expected: 3, found: 2 })
+    /// );
+    /// ```
+    pub fn eval_into(&self, out: &mut [T::Item]) -> Result<(), LengthMismatch>
+    where
+        T: Elementwise,
+    {
+        self.eval_into_with(&(), out)
+    }
+
+    /// Like [`eval_into`](#method.eval_into), but with the values of the placeholders taken from
+    /// a labelled paren like for [`eval_with`](../struct.Expr.html#method.eval_with), which are
+    /// broadcast to every element.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::placeholder;
+    /// use typing::expr::vector::vector;
+    /// use typing::tags::{A, B};
+    ///
+    /// let x = [1.0, 2.0, 3.0];
+    /// let e = placeholder::<f64, _>(A) * vector(&x) + placeholder::<f64, _>(B);
+    ///
+    /// let mut out = [0.0; 3];
+    /// e.eval_into_with(&paren!((A, 2.0), (B, 0.5)), &mut out).unwrap();
+    /// assert_eq!(out, [2.5, 4.5, 6.5]);
+    /// ```
+    pub fn eval_into_with<Env>(&self, env: &Env, out: &mut [T::Item]) -> Result<(), LengthMismatch>
+    where
+        T: Elementwise<Env>,
+    {
+        if let Some(expected) = self.0.length()? {
+            if expected != out.len() {
+                return Err(LengthMismatch {
+                    expected,
+                    found: out.len(),
+                });
+            }
+        }
+        for (i, x) in out.iter_mut().enumerate() {
+            *x = self.0.at(i, env);
+        }
+        Ok(())
+    }
+
+    /// Evaluate an element-wise expression into a new `Vec`, which is empty if the expression
+    /// doesn't contain any vector.
+    /// # Examples
+    /// ```
+    /// use typing::expr::vector::{vector, LengthMismatch};
+    ///
+    /// let x = [0.0, 1.0];
+    /// let y = [3, 5, 7];
+    /// assert_eq!((-vector(&x)).exp().eval_vec().unwrap(), [1.0, (-1.0f64).exp()]);
+    /// assert_eq!((vector(&y) % 4).eval_vec().unwrap(), [3, 1, 3]);
+    /// assert_eq!(
+    ///     (vector(&y) + vector(&y[1..])).eval_vec(),
+    ///     Err(LengthMismatch { expected: 3, found: 2 })
+    /// );
+    /// ```
+    pub fn eval_vec(&self) -> Result<Vec<T::Item>, LengthMismatch>
+    where
+        T: Elementwise,
+    {
+        self.eval_vec_with(&())
+    }
+
+    /// Like [`eval_vec`](#method.eval_vec), but with the values of the placeholders taken from a
+    /// labelled paren.
+    /// # Examples
+    /// ```
+    /// use typing::paren;
+    /// use typing::expr::var::placeholder;
+    /// use typing::expr::vector::vector;
+    /// use typing::tags::T;
+    ///
+    /// let x = [0.0, 1.0, 2.0];
+    /// let e = (vector(&x) - placeholder::<f64, _>(T)).pow::<typing::int::P2>();
+    /// assert_eq!(e.eval_vec_with(&paren!((T, 1.0))).unwrap(), [1.0, 0.0, 1.0]);
+    /// ```
+    pub fn eval_vec_with<Env>(&self, env: &Env) -> Result<Vec<T::Item>, LengthMismatch>
+    where
+        T: Elementwise<Env>,
+    {
+        let n = self.0.length()?.unwrap_or(0);
+        Ok((0..n).map(|i| self.0.at(i, env)).collect())
+    }
+}
@@ -38,7 +38,7 @@ pub struct Chain<F>(PhantomData<*const F>);
 impl<F> Clone for Chain<F> {
     #[inline]
     fn clone(&self) -> Self {
-        Self(PhantomData)
+        *self
     }
 }
 
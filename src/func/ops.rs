@@ -1,4 +1,6 @@
 use super::Func;
+use crate::num::{Promote, Promoted};
+use std::marker::PhantomData;
 
 macro_rules! impl_std_binop_fn {
     ($name:ident, $trait:ident, $method:ident) => {
@@ -28,6 +30,32 @@ impl_std_binop_fn!(ShlFn, Shl, shl);
 impl_std_binop_fn!(ShrFn, Shr, shr);
 impl_std_binop_fn!(SubFn, Sub, sub);
 
+// binary operators that first promote both arguments to a common numeric type
+macro_rules! impl_promote_binop_fn {
+    ($name:ident, $trait:ident, $method:ident) => {
+        #[derive(Copy, Clone, Debug, Default)]
+        pub struct $name;
+        impl<L, R> Func<(L, R)> for $name
+        where
+            L: Promote<R>,
+            Promoted<L, R>: std::ops::$trait,
+        {
+            type Output = <Promoted<L, R> as std::ops::$trait>::Output;
+            #[inline]
+            fn call((l, r): (L, R)) -> Self::Output {
+                let (l, r) = l.promote(r);
+                std::ops::$trait::$method(l, r)
+            }
+        }
+    };
+}
+
+impl_promote_binop_fn!(PromoteAddFn, Add, add);
+impl_promote_binop_fn!(PromoteDivFn, Div, div);
+impl_promote_binop_fn!(PromoteMulFn, Mul, mul);
+impl_promote_binop_fn!(PromoteRemFn, Rem, rem);
+impl_promote_binop_fn!(PromoteSubFn, Sub, sub);
+
 macro_rules! impl_std_unop_fn {
     ($name:ident, $trait:ident, $method:ident) => {
         #[derive(Copy, Clone, Debug, Default)]
@@ -77,4 +105,418 @@ impl_std_assignop_fn!(RemAssignFn, RemAssign, rem_assign);
 impl_std_assignop_fn!(ShlAssignFn, ShlAssign, shl_assign);
 impl_std_assignop_fn!(ShrAssignFn, ShrAssign, shr_assign);
 impl_std_assignop_fn!(SubAssignFn, SubAssign, sub_assign);
-// TODO: Drop, Index, IndexMut, Deref, DerefMut, others (e.g. Into?)
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IndexFn;
+
+impl<'a, L, I> Func<(&'a L, I)> for IndexFn
+where
+    L: std::ops::Index<I> + ?Sized,
+    L::Output: 'a,
+{
+    type Output = &'a L::Output;
+    #[inline]
+    fn call((l, i): (&'a L, I)) -> Self::Output {
+        &l[i]
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IndexMutFn;
+
+impl<'a, L, I> Func<(&'a mut L, I)> for IndexMutFn
+where
+    L: std::ops::IndexMut<I> + ?Sized,
+    L::Output: 'a,
+{
+    type Output = &'a mut L::Output;
+    #[inline]
+    fn call((l, i): (&'a mut L, I)) -> Self::Output {
+        &mut l[i]
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DerefFn;
+
+impl<'a, T> Func<&'a T> for DerefFn
+where
+    T: std::ops::Deref + ?Sized,
+{
+    type Output = &'a T::Target;
+    #[inline]
+    fn call(t: &'a T) -> Self::Output {
+        t
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DerefMutFn;
+
+impl<'a, T> Func<&'a mut T> for DerefMutFn
+where
+    T: std::ops::DerefMut + ?Sized,
+{
+    type Output = &'a mut T::Target;
+    #[inline]
+    fn call(t: &'a mut T) -> Self::Output {
+        t
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CloneFn;
+
+impl<'a, T: Clone> Func<&'a T> for CloneFn {
+    type Output = T;
+    #[inline]
+    fn call(t: &'a T) -> Self::Output {
+        t.clone()
+    }
+}
+
+/// Replaces a value with the default value of its type.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DefaultFn;
+
+impl<T: Default> Func<T> for DefaultFn {
+    type Output = T;
+    #[inline]
+    fn call(_: T) -> Self::Output {
+        T::default()
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DropFn;
+
+impl<T> Func<T> for DropFn {
+    type Output = ();
+    #[inline]
+    fn call(t: T) -> Self::Output {
+        drop(t)
+    }
+}
+
+/// Formats a value using its `Display` implementation.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ToStringFn;
+
+impl<T: ToString> Func<T> for ToStringFn {
+    type Output = String;
+    #[inline]
+    fn call(t: T) -> Self::Output {
+        t.to_string()
+    }
+}
+
+/// Appends a value to a `String` accumulator using its `Display` implementation, intended to
+/// be used with [`Paren::fold`].
+/// # Examples
+/// ```
+/// use typing::prelude::*;
+/// use typing::func::ops::DisplayFn;
+///
+/// let p = paren!(1, '+', 2.5, " = ", "3.5");
+/// assert_eq!(p.fold::<DisplayFn, _>(String::new()), "1+2.5 = 3.5");
+/// ```
+/// [`Paren::fold`]: ../../paren/trait.Paren.html#method.fold
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DisplayFn;
+
+impl<T: std::fmt::Display> Func<(String, T)> for DisplayFn {
+    type Output = String;
+    #[inline]
+    fn call((mut s, t): (String, T)) -> Self::Output {
+        use std::fmt::Write;
+        // writing to a String never fails
+        let _ = write!(s, "{}", t);
+        s
+    }
+}
+
+// conversion functors are parameterized by the target type
+macro_rules! impl_std_convert_fn {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub struct $name<T: ?Sized>(PhantomData<*const T>);
+
+        impl<T: ?Sized> Clone for $name<T> {
+            #[inline]
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T: ?Sized> Copy for $name<T> {}
+
+        impl<T: ?Sized> Default for $name<T> {
+            #[inline]
+            fn default() -> Self {
+                Self(PhantomData)
+            }
+        }
+
+        impl<T: ?Sized> std::fmt::Debug for $name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str(stringify!($name))
+            }
+        }
+    };
+}
+
+impl_std_convert_fn!(
+    /// Converts values into `T` using `Into<T>`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use typing::func::ops::IntoFn;
+    ///
+    /// let p = paren!(1u8, 2u16, 3u32, 4i32);
+    /// assert_eq!(p.map::<IntoFn<i64>>(), paren!(1i64, 2i64, 3i64, 4i64));
+    /// ```
+    IntoFn
+);
+
+impl<T, I: Into<T>> Func<I> for IntoFn<T> {
+    type Output = T;
+    #[inline]
+    fn call(input: I) -> Self::Output {
+        input.into()
+    }
+}
+
+impl_std_convert_fn!(
+    /// Converts values into `T` using `From`.
+    FromFn
+);
+
+impl<T: From<I>, I> Func<I> for FromFn<T> {
+    type Output = T;
+    #[inline]
+    fn call(input: I) -> Self::Output {
+        T::from(input)
+    }
+}
+
+impl_std_convert_fn!(
+    /// Attempts to convert values into `T` using `TryInto<T>`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use typing::func::ops::TryIntoFn;
+    ///
+    /// let p = paren!(1i64, 300u32, -1i8);
+    /// let paren_pat!(a, b, c) = p.map::<TryIntoFn<u8>>();
+    /// assert_eq!(a, Ok(1));
+    /// assert!(b.is_err());
+    /// assert!(c.is_err());
+    /// ```
+    TryIntoFn
+);
+
+impl<T, I: std::convert::TryInto<T>> Func<I> for TryIntoFn<T> {
+    type Output = Result<T, I::Error>;
+    #[inline]
+    fn call(input: I) -> Self::Output {
+        std::convert::TryInto::try_into(input)
+    }
+}
+
+impl_std_convert_fn!(
+    /// Attempts to convert values into `T` using `TryFrom`.
+    TryFromFn
+);
+
+impl<T: std::convert::TryFrom<I>, I> Func<I> for TryFromFn<T> {
+    type Output = Result<T, T::Error>;
+    #[inline]
+    fn call(input: I) -> Self::Output {
+        T::try_from(input)
+    }
+}
+
+impl_std_convert_fn!(
+    /// Borrows references as `&T` using `AsRef<T>`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use typing::func::ops::AsRefFn;
+    ///
+    /// let s = String::from("foo");
+    /// let p = paren!("bar", &s, &std::path::Path::new("baz"));
+    /// let _: Paren!(&std::ffi::OsStr, &std::ffi::OsStr, &std::ffi::OsStr) =
+    ///     p.map::<AsRefFn<std::ffi::OsStr>>();
+    /// ```
+    AsRefFn
+);
+
+impl<'a, T: ?Sized + 'a, I: AsRef<T> + ?Sized> Func<&'a I> for AsRefFn<T> {
+    type Output = &'a T;
+    #[inline]
+    fn call(input: &'a I) -> Self::Output {
+        input.as_ref()
+    }
+}
+
+impl_std_convert_fn!(
+    /// Borrows mutable references as `&mut T` using `AsMut<T>`.
+    AsMutFn
+);
+
+impl<'a, T: ?Sized + 'a, I: AsMut<T> + ?Sized> Func<&'a mut I> for AsMutFn<T> {
+    type Output = &'a mut T;
+    #[inline]
+    fn call(input: &'a mut I) -> Self::Output {
+        input.as_mut()
+    }
+}
+
+impl_std_convert_fn!(
+    /// Borrows references as `&T` using `Borrow<T>`.
+    BorrowFn
+);
+
+impl<'a, T: ?Sized + 'a, I: std::borrow::Borrow<T> + ?Sized> Func<&'a I> for BorrowFn<T> {
+    type Output = &'a T;
+    #[inline]
+    fn call(input: &'a I) -> Self::Output {
+        input.borrow()
+    }
+}
+
+impl_std_convert_fn!(
+    /// Borrows mutable references as `&mut T` using `BorrowMut<T>`.
+    BorrowMutFn
+);
+
+impl<'a, T: ?Sized + 'a, I: std::borrow::BorrowMut<T> + ?Sized> Func<&'a mut I> for BorrowMutFn<T> {
+    type Output = &'a mut T;
+    #[inline]
+    fn call(input: &'a mut I) -> Self::Output {
+        input.borrow_mut()
+    }
+}
+
+macro_rules! impl_std_cmp_fn {
+    ($name:ident, $trait:ident, $method:ident) => {
+        #[derive(Copy, Clone, Debug, Default)]
+        pub struct $name;
+        impl<L, R> Func<(L, R)> for $name
+        where
+            L: std::cmp::$trait<R>,
+        {
+            type Output = bool;
+            #[inline]
+            fn call((l, r): (L, R)) -> Self::Output {
+                std::cmp::$trait::$method(&l, &r)
+            }
+        }
+    };
+}
+
+impl_std_cmp_fn!(EqFn, PartialEq, eq);
+impl_std_cmp_fn!(NeFn, PartialEq, ne);
+impl_std_cmp_fn!(LtFn, PartialOrd, lt);
+impl_std_cmp_fn!(LeFn, PartialOrd, le);
+impl_std_cmp_fn!(GtFn, PartialOrd, gt);
+impl_std_cmp_fn!(GeFn, PartialOrd, ge);
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PartialCmpFn;
+
+impl<L, R> Func<(L, R)> for PartialCmpFn
+where
+    L: PartialOrd<R>,
+{
+    type Output = Option<std::cmp::Ordering>;
+    #[inline]
+    fn call((l, r): (L, R)) -> Self::Output {
+        l.partial_cmp(&r)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+pub struct CmpFn;
+
+impl<T: Ord> Func<(T, T)> for CmpFn {
+    type Output = std::cmp::Ordering;
+    #[inline]
+    fn call((l, r): (T, T)) -> Self::Output {
+        l.cmp(&r)
+    }
+}
+
+/// Returns the smaller of two values of the same type, preferring the left value if they compare
+/// equal.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MinFn;
+
+impl<T: PartialOrd> Func<(T, T)> for MinFn {
+    type Output = T;
+    #[inline]
+    fn call((l, r): (T, T)) -> Self::Output {
+        if r < l {
+            r
+        } else {
+            l
+        }
+    }
+}
+
+/// Returns the larger of two values of the same type, preferring the right value if they compare
+/// equal.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MaxFn;
+
+impl<T: PartialOrd> Func<(T, T)> for MaxFn {
+    type Output = T;
+    #[inline]
+    fn call((l, r): (T, T)) -> Self::Output {
+        if r < l {
+            l
+        } else {
+            r
+        }
+    }
+}
+
+/// Returns the smaller of two numbers after promoting both to a common type as defined by
+/// [`Promote`], preferring the left value if they compare equal.
+///
+/// [`Promote`]: ../../num/trait.Promote.html
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PromoteMinFn;
+
+impl<L, R> Func<(L, R)> for PromoteMinFn
+where
+    L: Promote<R>,
+    Promoted<L, R>: PartialOrd,
+{
+    type Output = Promoted<L, R>;
+    #[inline]
+    fn call((l, r): (L, R)) -> Self::Output {
+        let (l, r) = l.promote(r);
+        MinFn::call((l, r))
+    }
+}
+
+/// Returns the larger of two numbers after promoting both to a common type as defined by
+/// [`Promote`], preferring the right value if they compare equal.
+///
+/// [`Promote`]: ../../num/trait.Promote.html
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PromoteMaxFn;
+
+impl<L, R> Func<(L, R)> for PromoteMaxFn
+where
+    L: Promote<R>,
+    Promoted<L, R>: PartialOrd,
+{
+    type Output = Promoted<L, R>;
+    #[inline]
+    fn call((l, r): (L, R)) -> Self::Output {
+        let (l, r) = l.promote(r);
+        MaxFn::call((l, r))
+    }
+}
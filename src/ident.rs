@@ -1,4 +1,4 @@
-use crate::boolean::Bool;
+use crate::boolean::{Bool, BoolIfElse, IfElse};
 use crate::func::Func;
 use std::marker::PhantomData;
 
@@ -53,6 +53,46 @@ mod impls {
     }
 }
 
+/// Type-level `match` over distinguishable types, with arms given as a list of `Case`s that is
+/// terminated by either `Otherwise` (for a fallback arm) or `()`. See [`match_type`].
+///
+/// [`match_type`]: ../macro.match_type.html
+pub trait TypeMatch<Arms> {
+    type Output;
+}
+
+pub type TypeMatched<T, Arms> = <T as TypeMatch<Arms>>::Output;
+
+/// Match arm resulting in `R` if the matched type is the same as `P`.
+#[derive(Debug)]
+pub struct Case<P, R>(PhantomData<*const (P, R)>);
+
+/// Fallback match arm resulting in `R`.
+#[derive(Debug)]
+pub struct Otherwise<R>(PhantomData<*const R>);
+
+/// Result of a [`TypeMatch`] when no arm matched.
+///
+/// [`TypeMatch`]: trait.TypeMatch.html
+#[derive(Debug)]
+pub struct NoMatch;
+
+impl<T, P, R, Rest> TypeMatch<(Case<P, R>, Rest)> for T
+where
+    T: TypeEq<P> + TypeMatch<Rest>,
+    IsEq<T, P>: BoolIfElse<R, TypeMatched<T, Rest>>,
+{
+    type Output = IfElse<IsEq<T, P>, R, TypeMatched<T, Rest>>;
+}
+
+impl<T, R> TypeMatch<Otherwise<R>> for T {
+    type Output = R;
+}
+
+impl<T> TypeMatch<()> for T {
+    type Output = NoMatch;
+}
+
 #[derive(Debug)]
 pub struct TypeEqFn<T>(PhantomData<*const T>);
 
@@ -0,0 +1,101 @@
+use std::fmt::Debug;
+
+/// Type-level zero.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Z0;
+
+/// Type-level successor of a non-negative integer.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Succ<N>(N);
+
+/// Type-level predecessor of a non-positive integer.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Pred<N>(N);
+
+pub type P1 = Succ<Z0>;
+pub type P2 = Succ<P1>;
+pub type P3 = Succ<P2>;
+pub type P4 = Succ<P3>;
+pub type N1 = Pred<Z0>;
+pub type N2 = Pred<N1>;
+pub type N3 = Pred<N2>;
+pub type N4 = Pred<N3>;
+
+/// Type-level integers, which are in normal form so that equal integers are the same type, e.g.
+/// there's no `Succ<Pred<Z0>>`.
+/// # Examples
+/// ```
+/// use typing::int::{Int, Minus, Plus, Times, N2, P1, P3};
+///
+/// assert_eq!(<Plus<P3, N2> as Int>::VALUE, 1);
+/// assert_eq!(<Minus<N2, P3> as Int>::VALUE, -5);
+/// assert_eq!(<Times<N2, P3> as Int>::VALUE, -6);
+/// let _: P1 = Plus::<P3, N2>::default();
+/// ```
+pub trait Int: Debug + Default + Clone + Copy {
+    const VALUE: i32;
+}
+
+impl Int for Z0 {
+    const VALUE: i32 = 0;
+}
+
+impl<N: Int> Int for Succ<N> {
+    const VALUE: i32 = N::VALUE + 1;
+}
+
+impl<N: Int> Int for Pred<N> {
+    const VALUE: i32 = N::VALUE - 1;
+}
+
+#[rustfmt::skip]
+mod ops {
+    use super::{Int, Pred, Succ, Z0};
+    use crate::boolean::{Bool, False, True};
+
+    pub trait IntIsZero { type Output: Bool; }
+    pub type IsZero<N> = <N as IntIsZero>::Output;
+    impl IntIsZero for Z0 { type Output = True; }
+    impl<N> IntIsZero for Succ<N> { type Output = False; }
+    impl<N> IntIsZero for Pred<N> { type Output = False; }
+
+    pub trait IntInc { type Output: Int; }
+    pub type Incr<N> = <N as IntInc>::Output;
+    impl IntInc for Z0 { type Output = Succ<Z0>; }
+    impl<N: Int> IntInc for Succ<N> { type Output = Succ<Succ<N>>; }
+    impl<N: Int> IntInc for Pred<N> { type Output = N; }
+
+    pub trait IntDec { type Output: Int; }
+    pub type Decr<N> = <N as IntDec>::Output;
+    impl IntDec for Z0 { type Output = Pred<Z0>; }
+    impl<N: Int> IntDec for Succ<N> { type Output = N; }
+    impl<N: Int> IntDec for Pred<N> { type Output = Pred<Pred<N>>; }
+
+    pub trait IntNeg { type Output: Int; }
+    pub type Negated<N> = <N as IntNeg>::Output;
+    impl IntNeg for Z0 { type Output = Z0; }
+    impl<N: IntNeg> IntNeg for Succ<N> where Negated<N>: IntDec { type Output = Decr<Negated<N>>; }
+    impl<N: IntNeg> IntNeg for Pred<N> where Negated<N>: IntInc { type Output = Incr<Negated<N>>; }
+
+    pub trait IntAdd<R> { type Output: Int; }
+    pub type Plus<L, R> = <L as IntAdd<R>>::Output;
+    impl<R: Int> IntAdd<R> for Z0 { type Output = R; }
+    impl<N: IntAdd<Incr<R>>, R: IntInc> IntAdd<R> for Succ<N> { type Output = Plus<N, Incr<R>>; }
+    impl<N: IntAdd<Decr<R>>, R: IntDec> IntAdd<R> for Pred<N> { type Output = Plus<N, Decr<R>>; }
+
+    pub trait IntSub<R> { type Output: Int; }
+    pub type Minus<L, R> = <L as IntSub<R>>::Output;
+    impl<L: IntAdd<Negated<R>>, R: IntNeg> IntSub<R> for L { type Output = Plus<L, Negated<R>>; }
+
+    pub trait IntMul<R> { type Output: Int; }
+    pub type Times<L, R> = <L as IntMul<R>>::Output;
+    impl<R> IntMul<R> for Z0 { type Output = Z0; }
+    impl<N: IntMul<R>, R> IntMul<R> for Succ<N> where Times<N, R>: IntAdd<R> {
+        type Output = Plus<Times<N, R>, R>;
+    }
+    impl<N: IntMul<R>, R> IntMul<R> for Pred<N> where Times<N, R>: IntSub<R> {
+        type Output = Minus<Times<N, R>, R>;
+    }
+}
+
+pub use ops::*;
@@ -2,6 +2,8 @@ pub mod boolean;
 pub mod expr;
 pub mod func;
 pub mod ident;
+pub mod int;
+pub mod num;
 pub mod option;
 pub mod paren;
 pub mod tags;
@@ -12,7 +14,7 @@ pub mod prelude {
     pub use crate::boolean::{False, True};
     pub use crate::func::{Apply, CanApply, Func};
     pub use crate::option::{NoneType, SomeType};
-    pub use crate::{declare_func, impl_func, paren, paren::Paren, paren_pat, Paren};
+    pub use crate::{declare_func, impl_func, match_type, paren, paren::Paren, paren_pat, Paren};
     pub use crate::{T0, T1};
 }
 
@@ -8,9 +8,13 @@
 ///     // lifetimes or generic parameters can be put before the function in brackets
 ///     ['a] |y: &'a mut f32| { *y *= 2.0; },
 ///     [T: Copy] |y: [T; 1]| -> [T; 2] { [y[0], y[0]] },
+///     // including const generic parameters
+///     ['a, const N: usize] |y: &'a [u8; N]| -> usize { N },
 ///     // attributes are allowed as well, and will be applied to the function definition
 ///     #[inline]
 ///     |x: usize| -> (usize, usize) { (x, x) },
+///     // and where clauses can be put between the return type and the body
+///     [T] |x: Vec<T>| -> T where T: Default + Copy { x.first().copied().unwrap_or_default() },
 /// });
 ///
 /// let mut x = 1.0;
@@ -20,39 +24,132 @@
 /// assert_eq!(Double::call(2.0), 4.0);
 /// assert_eq!(Double::call(2), (2, 2));
 /// assert_eq!(Double::call(["hello"]), ["hello", "hello"]);
+/// assert_eq!(Double::call(&[0u8; 5]), 5);
+/// assert_eq!(Double::call(vec![3, 4]), 3);
+/// ```
+///
+/// Generic types can be given parameters and a where clause that apply to every function.
+/// ```
+/// use typing::{impl_func, func::Func};
+/// use std::marker::PhantomData;
+///
+/// struct Scale<const N: i64>;
+/// impl_func!([const N: i64] for Scale<N> {
+///     |x: i64| -> i64 { x * N },
+///     |x: f64| -> f64 { x * N as f64 },
+/// });
+///
+/// struct Wrap<T>(PhantomData<T>);
+/// impl_func!([T, U] for Wrap<T> where T: From<U> {
+///     |x: U| -> Option<T> { Some(T::from(x)) },
+/// });
+///
+/// assert_eq!(Scale::<3>::call(2), 6);
+/// assert_eq!(Scale::<-1>::call(2.5), -2.5);
+/// assert_eq!(Wrap::<u64>::call(5u8), Some(5u64));
 /// ```
 /// [`Func`]: func/trait.Func.html
 #[macro_export]
 macro_rules! impl_func {
-    (for $typ:ty {
-            // ignore any commas
-            $(,)?
-            // optional function meta
-            $(#[$($meta:meta)*])*
-            // lifetimes/generic params
-            $([$($c:tt $(: $c0:ident $(+ $cN:ident)*)?),*])?
-            // function arg
-            |$arg:tt: $arg_type:ty|
-            // optional return type
-            $(-> $ret:ty)?
-            // function body
-            $body:block
-            // rest of the functions
-            $($rest:tt)*
+    // type-level generic parameters and where clauses
+    ($([$($c:tt $($c1:ident)? $(: $c0:ident $(+ $cN:ident)*)?),*])? for $typ:ty {
+        $($fns:tt)*
     }) => {
-        impl<$($($c $(: $c0 $(+ $cN)*)?,)*)?> $crate::func::Func<$arg_type> for $typ
+        $crate::impl_func!(@fns [$($($c $($c1)? $(: $c0 $(+ $cN)*)?,)*)?] [] [$typ] $($fns)*);
+    };
+    ($([$($c:tt $($c1:ident)? $(: $c0:ident $(+ $cN:ident)*)?),*])? for $typ:ty where
+        $($rest:tt)*
+    ) => {
+        $crate::impl_func!(
+            @impl_where [$($($c $($c1)? $(: $c0 $(+ $cN)*)?,)*)?] [$typ] [] $($rest)*
+        );
+    };
+    (@impl_where $g:tt $typ:tt [$($w:tt)*] $(,)? { $($fns:tt)* }) => {
+        $crate::impl_func!(@fns $g [$($w)* ,] $typ $($fns)*);
+    };
+    (@impl_where $g:tt $typ:tt [$($w:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::impl_func!(@impl_where $g $typ [$($w)* $t] $($rest)*);
+    };
+    // functions with a where clause
+    (@fns $g:tt $w:tt $typ:tt
+        // ignore any commas
+        $(,)?
+        // optional function meta
+        $(#[$($meta:meta)*])*
+        // lifetimes/generic params
+        $([$($c:tt $($c1:ident)? $(: $c0:ident $(+ $cN:ident)*)?),*])?
+        // function arg
+        |$arg:tt: $arg_type:ty|
+        // optional return type
+        $(-> $ret:ty)?
+        where $($rest:tt)*
+    ) => {
+        $crate::impl_func!(
+            @fn_where $g $w $typ
+            [$(#[$($meta)*])*]
+            [$($($c $($c1)? $(: $c0 $(+ $cN)*)?,)*)?]
+            ($arg: $arg_type)
+            ($($ret)?)
+            []
+            $($rest)*
+        );
+    };
+    (@fn_where $g:tt $w:tt $typ:tt $m:tt $fg:tt $sig:tt $ret:tt [$($fw:tt)*]
+        $(,)? { $($body:tt)* } $($rest:tt)*
+    ) => {
+        $crate::impl_func!(@emit $g $w $typ $m $fg [$($fw)* ,] $sig $ret { $($body)* });
+        $crate::impl_func!(@fns $g $w $typ $($rest)*);
+    };
+    (@fn_where $g:tt $w:tt $typ:tt $m:tt $fg:tt $sig:tt $ret:tt [$($fw:tt)*]
+        $t:tt $($rest:tt)*
+    ) => {
+        $crate::impl_func!(@fn_where $g $w $typ $m $fg $sig $ret [$($fw)* $t] $($rest)*);
+    };
+    // functions without a where clause
+    (@fns $g:tt $w:tt $typ:tt
+        $(,)?
+        $(#[$($meta:meta)*])*
+        $([$($c:tt $($c1:ident)? $(: $c0:ident $(+ $cN:ident)*)?),*])?
+        |$arg:tt: $arg_type:ty|
+        $(-> $ret:ty)?
+        // function body
+        { $($body:tt)* }
+        // rest of the functions
+        $($rest:tt)*
+    ) => {
+        $crate::impl_func!(
+            @emit $g $w $typ
+            [$(#[$($meta)*])*]
+            [$($($c $($c1)? $(: $c0 $(+ $cN)*)?,)*)?]
+            []
+            ($arg: $arg_type)
+            ($($ret)?)
+            { $($body)* }
+        );
+        $crate::impl_func!(@fns $g $w $typ $($rest)*);
+    };
+    (@fns $g:tt $w:tt $typ:tt $(,)?) => {};
+    // note: generic parameters and where clauses are passed around with trailing commas so that
+    // the ones for the type and the function can just be concatenated
+    (@emit [$($g:tt)*] [$($w:tt)*] [$typ:ty] [$(#[$($meta:meta)*])*] [$($fg:tt)*] [$($fw:tt)*]
+        ($arg:tt: $arg_type:ty) $ret:tt { $($body:tt)* }
+    ) => {
+        #[allow(unused_parens)]
+        impl<$($g)* $($fg)*> $crate::func::Func<$arg_type> for $typ
+        where
+            $($w)* $($fw)*
         {
             // note: we can put the return type in parentheses since there shouldn't be a comma
-            type Output = ($($ret)?);
+            type Output = $ret;
 
             $(#[$($meta)*])*
-            fn call($arg: $arg_type) -> Self::Output $body
+            fn call($arg: $arg_type) -> Self::Output {
+                $($body)*
+            }
         }
-
-        $crate::impl_func!(for $typ { $($rest)* });
     };
-    (for $typ:ty {$(,)?}) => {};
 }
+
 /// Declare an empty struct type with an optional name and implement the [`Func`] trait for it.
 ///
 /// Also implements a member function `call` that can be used for the case of anonymous structs.
@@ -76,6 +173,65 @@ macro_rules! impl_func {
 /// assert_eq!(vec![true, false], f.call((vec![true], vec![false])));
 /// assert_eq!(vec![0, 0, 1, 1], f.call((vec![0, 0], vec![1, 1])));
 /// ```
+///
+/// Named structs can also have type and const generic parameters, which are available to every
+/// function. Instances can be created with `Default`.
+/// ```
+/// use typing::{declare_func, func::Func, paren, paren::Paren};
+/// declare_func!(AddN<const N: i64> {
+///     |x: i64| -> i64 { x + N },
+/// });
+///
+/// declare_func!(CastTo<T> {
+///     [U] |x: U| -> T where U: Into<T> { x.into() },
+/// });
+///
+/// let p = paren!(1, 2, 3);
+/// assert_eq!(p.map::<AddN<10>>(), paren!(11, 12, 13));
+/// assert_eq!(p.map::<AddN<-1>>(), paren!(0, 1, 2));
+///
+/// let p = paren!(1u8, 2u16, 3u32);
+/// assert_eq!(p.map::<CastTo<u64>>(), paren!(1u64, 2u64, 3u64));
+/// assert_eq!(CastTo::<f64>::default().call(2.5f32), 2.5f64);
+/// ```
+///
+/// Functions taking types that implement [`TypeId`] can also be declared by matching on the
+/// argument type, including a fallback `_` arm for every other type. This can't be expressed with
+/// [`impl_func`] directly since the fallback would overlap with the other arms.
+/// ```
+/// use typing::{declare_func, paren, paren::Paren, Paren};
+/// use typing::option::{NoneType, SomeType};
+/// use typing::tags::{A, B, X, Y, Z};
+///
+/// declare_func!(KeepXY match {
+///     X => |x: X| -> SomeType<X> { SomeType(x) },
+///     // arms can map to other types as well
+///     Y => |_: Y| -> SomeType<char> { SomeType('y') },
+///     _ => [T] |_: T| -> NoneType { NoneType },
+/// });
+///
+/// let p: Paren!(X, char) = paren!(A, X, B, Y, Z).filter_map::<KeepXY>();
+/// assert_eq!(p.1, paren!('y'));
+///
+/// // without a fallback arm, only the listed types are accepted
+/// declare_func!(IsX match {
+///     X => |_: X| -> bool { true },
+///     Y => |_: Y| -> bool { false },
+/// });
+/// assert_eq!(paren!(X, Y, X).map::<IsX>(), paren!(true, false, true));
+/// ```
+///
+/// The fallback arm has to come last:
+/// ```compile_fail
+/// use typing::declare_func;
+/// use typing::tags::X;
+///
+/// declare_func!(IsX match {
+///     _ => [T] |_: T| -> bool { false },
+///     X => |_: X| -> bool { true },
+/// });
+/// ```
+/// [`TypeId`]: ident/trait.TypeId.html
 /// [`Func`]: func/trait.Func.html
 /// [`impl_func`]: macro.impl_func.html
 #[macro_export]
@@ -93,6 +249,90 @@ macro_rules! declare_func {
             }
         }
     };
+    ($name:ident<$($c:tt $($c1:ident)? $(: $c0:ident $(+ $cN:ident)*)?),+> { $($t:tt)* }) => {
+        struct $name<$($c $($c1)? $(: $c0 $(+ $cN)*)?),+>(
+            ::std::marker::PhantomData<*const ($($crate::__generic_phantom!($c $($c1)?),)+)>
+        );
+        $crate::impl_func!(
+            [$($c $($c1)? $(: $c0 $(+ $cN)*)?),+]
+            for $name<$($crate::__generic_arg!($c $($c1)?)),+> { $($t)* }
+        );
+        impl<$($c $($c1)? $(: $c0 $(+ $cN)*)?),+> Default
+            for $name<$($crate::__generic_arg!($c $($c1)?)),+>
+        {
+            #[inline]
+            fn default() -> Self {
+                $name(::std::marker::PhantomData)
+            }
+        }
+        impl<$($c $($c1)? $(: $c0 $(+ $cN)*)?),+> $name<$($crate::__generic_arg!($c $($c1)?)),+> {
+            #[inline]
+            pub fn call<FuncArg, FuncRet>(&self, arg: FuncArg) -> FuncRet
+            where
+                FuncArg: $crate::func::CanApply<Self, Output = FuncRet>,
+            {
+                FuncArg::apply(arg)
+            }
+        }
+    };
+    ($name:ident match { $($arms:tt)* }) => {
+        struct $name;
+        // arms are implemented for a helper type that isn't visible outside of this block
+        const _: () = {
+            struct Arm<P>(::std::marker::PhantomData<*const P>);
+            struct Fallback;
+            $crate::declare_func!(@match $name [] $($arms)*);
+        };
+        impl $name {
+            #[inline]
+            pub fn call<T, R>(&self, arg: T) -> R
+            where
+                T: $crate::func::CanApply<Self, Output = R>,
+            {
+                T::apply(arg)
+            }
+        }
+    };
+    (@match $name:ident [$($p:ty),*] $(,)?) => {
+        $crate::declare_func!(@dispatch $name [$($p => Arm<$p>,)*]);
+    };
+    (@match $name:ident [$($p:ty),*] $(,)? _ => $($rest:tt)*) => {
+        $crate::declare_func!(@match_default $name [$($p),*] [] $($rest)*);
+    };
+    (@match $name:ident [$($p:ty),*] $(,)? $arm:ty => $($rest:tt)*) => {
+        $crate::declare_func!(@match_arm $name [$($p),*] $arm [] $($rest)*);
+    };
+    (@match_arm $name:ident [$($p:ty),*] $arm:ty [$($f:tt)*] { $($body:tt)* } $($rest:tt)*) => {
+        $crate::impl_func!(for Arm<$arm> { $($f)* { $($body)* } });
+        $crate::declare_func!(@match $name [$($p,)* $arm] $($rest)*);
+    };
+    (@match_arm $name:ident $ps:tt $arm:ty [$($f:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::declare_func!(@match_arm $name $ps $arm [$($f)* $t] $($rest)*);
+    };
+    (@match_default $name:ident [$($p:ty),*] [$($f:tt)*] { $($body:tt)* } $(,)?) => {
+        $crate::impl_func!(for Fallback { $($f)* { $($body)* } });
+        $crate::declare_func!(@dispatch $name [$($p => Arm<$p>,)* _ => Fallback]);
+    };
+    (@match_default $name:ident $ps:tt $f:tt { $($body:tt)* } , $($rest:tt)+) => {
+        compile_error!("the `_` arm of `declare_func!` must be the last arm");
+    };
+    (@match_default $name:ident $ps:tt [$($f:tt)*] $t:tt $($rest:tt)*) => {
+        $crate::declare_func!(@match_default $name $ps [$($f)* $t] $($rest)*);
+    };
+    (@dispatch $name:ident [$($arms:tt)*]) => {
+        impl<I> $crate::func::Func<I> for $name
+        where
+            I: $crate::ident::TypeMatch<$crate::__match_arms!($($arms)*)>,
+            I: $crate::func::CanApply<$crate::match_type!(I { $($arms)* })>,
+        {
+            type Output = $crate::func::Apply<$crate::match_type!(I { $($arms)* }), I>;
+
+            #[inline]
+            fn call(input: I) -> Self::Output {
+                $crate::func::CanApply::apply(input)
+            }
+        }
+    };
     ({ $($t:tt)* }) => {{
         $crate::declare_func!( F { $($t)* });
         F
@@ -122,6 +362,46 @@ macro_rules! assert_type_eq {
     };
 }
 
+/// Resolve to one of several types at compile-time depending on which type (implementing
+/// [`TypeId`]) a given type is equal to, with an optional `_` fallback arm. Without a fallback arm,
+/// types that do not match any arm resolve to [`NoMatch`].
+/// # Examples
+/// ```
+/// use typing::{assert_type_eq, match_type};
+/// use typing::ident::NoMatch;
+/// use typing::tags::{X, Y, Z};
+///
+/// type Select<T> = match_type!(T {
+///     X => u8,
+///     Y => Vec<u16>,
+///     _ => (),
+/// });
+///
+/// assert_type_eq!(Select<X>, u8);
+/// assert_type_eq!(Select<Y>, Vec<u16>);
+/// assert_type_eq!(Select<Z>, ());
+/// assert_type_eq!(match_type!(Z { X => u8 }), NoMatch);
+/// ```
+/// [`TypeId`]: ident/trait.TypeId.html
+/// [`NoMatch`]: ident/struct.NoMatch.html
+#[macro_export]
+macro_rules! match_type {
+    ($t:ty { $($arms:tt)* }) => {
+        $crate::ident::TypeMatched<$t, $crate::__match_arms!($($arms)*)>
+    };
+}
+
+/// Builds the list of arms for a [`TypeMatch`](ident/trait.TypeMatch.html).
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __match_arms {
+    ($(,)?) => { () };
+    (_ => $r:ty $(,)?) => { $crate::ident::Otherwise<$r> };
+    ($p:ty => $r:ty $(, $($rest:tt)*)?) => {
+        ($crate::ident::Case<$p, $r>, $crate::__match_arms!($($($rest)*)?))
+    };
+}
+
 /// Replace first argument by the second, interprets as tts.
 #[doc(hidden)]
 #[macro_export]
@@ -131,6 +411,31 @@ macro_rules! __rep {
     };
 }
 
+/// Name of a generic parameter for use as a generic argument.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __generic_arg {
+    (const $name:ident) => {
+        $name
+    };
+    ($name:ident) => {
+        $name
+    };
+}
+
+/// Type used to mark usage of a generic parameter in a `PhantomData`, const parameters don't need
+/// to be marked so they are replaced by `()`.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __generic_phantom {
+    (const $name:ident) => {
+        ()
+    };
+    ($name:ident) => {
+        $name
+    };
+}
+
 /// Counts number of tts recursively.
 #[doc(hidden)]
 #[macro_export]
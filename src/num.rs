@@ -0,0 +1,141 @@
+/// Primitive numeric types with known additive and multiplicative identities.
+pub trait Num: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+}
+
+macro_rules! impl_num {
+    ($zero:literal, $one:literal; $($t:ty)*) => {$(
+        impl Num for $t {
+            const ZERO: Self = $zero;
+            const ONE: Self = $one;
+        }
+    )*};
+}
+
+impl_num!(0, 1; i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+impl_num!(0.0, 1.0; f32 f64);
+
+/// Primitive floating point types, providing the elementary functions used by expressions.
+pub trait Float:
+    Num
+    + std::ops::Neg<Output = Self>
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+    + std::ops::Div<Output = Self>
+    + PartialOrd
+{
+    fn from_i32(n: i32) -> Self;
+    fn from_i64(n: i64) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn tanh(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn powf(self, n: Self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($($t:ident)*) => {$(
+        impl Float for $t {
+            #[inline(always)]
+            fn from_i32(n: i32) -> Self {
+                n as $t
+            }
+
+            #[inline(always)]
+            fn from_i64(n: i64) -> Self {
+                n as $t
+            }
+
+            impl_float!(@fns $t; sin cos exp ln sqrt tanh);
+
+            #[inline(always)]
+            fn powi(self, n: i32) -> Self {
+                $t::powi(self, n)
+            }
+
+            #[inline(always)]
+            fn powf(self, n: Self) -> Self {
+                $t::powf(self, n)
+            }
+        }
+    )*};
+    (@fns $t:ident; $($f:ident)*) => {$(
+        #[inline(always)]
+        fn $f(self) -> Self {
+            $t::$f(self)
+        }
+    )*};
+}
+
+impl_float!(f32 f64);
+
+/// Type-level numeric promotion: the smallest primitive numeric type that both `Self` and `R` can
+/// be converted into, following the same rules as NumPy. Unsigned integers promote to wider
+/// signed integers when mixed with signed ones, and integers promote to floats wide enough to
+/// hold them exactly where possible. `usize` and `isize` are treated as 64 bits wide.
+///
+/// Some promotions are lossy: `u64`, `i64`, `usize` and `isize` mixed with floats or with an
+/// integer of the opposite signedness promote to `f64`, which only represents integers up to
+/// 2<sup>53</sup> exactly, and on 32-bit targets `u32` mixed with `isize` is truncated.
+/// # Examples
+/// ```
+/// use typing::assert_type_eq;
+/// use typing::num::{Promote, Promoted};
+///
+/// assert_type_eq!(Promoted<u8, u32>, u32);
+/// assert_type_eq!(Promoted<u8, i8>, i16);
+/// assert_type_eq!(Promoted<i16, f32>, f32);
+/// assert_type_eq!(Promoted<i32, f32>, f64);
+/// assert_type_eq!(Promoted<u64, i64>, f64);
+/// assert_type_eq!(Promoted<u16, usize>, usize);
+/// assert_type_eq!(Promoted<i8, isize>, isize);
+///
+/// assert_eq!(3u8.promote(-2i32), (3i32, -2i32));
+/// // precision is lost above 2^53
+/// assert_eq!((u64::MAX - 1).promote(0i64).0, u64::MAX as f64);
+/// ```
+pub trait Promote<R> {
+    type Output;
+    fn promote(self, r: R) -> (Self::Output, Self::Output);
+}
+
+pub type Promoted<L, R> = <L as Promote<R>>::Output;
+
+macro_rules! promote_table {
+    (@row $l:ident [$($r:ident)*] [$($out:ident)*]) => {
+        $(
+            impl Promote<$r> for $l {
+                type Output = $out;
+                #[inline(always)]
+                fn promote(self, r: $r) -> (Self::Output, Self::Output) {
+                    (self as $out, r as $out)
+                }
+            }
+        )*
+    };
+    ($header:tt; $($l:ident => $out:tt,)*) => {
+        $(promote_table!(@row $l $header $out);)*
+    };
+}
+
+#[rustfmt::skip]
+promote_table!(
+    [u8    u16   u32   u64   usize i8    i16   i32   i64   isize f32   f64];
+    u8    => [u8    u16   u32   u64   usize i16   i16   i32   i64   isize f32   f64],
+    u16   => [u16   u16   u32   u64   usize i32   i32   i32   i64   isize f32   f64],
+    u32   => [u32   u32   u32   u64   usize i64   i64   i64   i64   isize f64   f64],
+    u64   => [u64   u64   u64   u64   u64   f64   f64   f64   f64   f64   f64   f64],
+    usize => [usize usize usize u64   usize f64   f64   f64   f64   f64   f64   f64],
+    i8    => [i16   i32   i64   f64   f64   i8    i16   i32   i64   isize f32   f64],
+    i16   => [i16   i32   i64   f64   f64   i16   i16   i32   i64   isize f32   f64],
+    i32   => [i32   i32   i64   f64   f64   i32   i32   i32   i64   isize f64   f64],
+    i64   => [i64   i64   i64   f64   f64   i64   i64   i64   i64   i64   f64   f64],
+    isize => [isize isize isize f64   f64   isize isize isize i64   isize f64   f64],
+    f32   => [f32   f32   f64   f64   f64   f32   f32   f64   f64   f64   f32   f64],
+    f64   => [f64   f64   f64   f64   f64   f64   f64   f64   f64   f64   f64   f64],
+);
@@ -1 +1,59 @@
-// TODO
+use crate::func::Func;
+use crate::Paren;
+
+/// Conversion of parens where every element has the same type into arrays.
+pub trait IntoArray {
+    type Output;
+    fn into_array(self) -> Self::Output;
+}
+
+pub type IntoArrayOut<T> = <T as IntoArray>::Output;
+
+macro_rules! impl_into_array {
+    ($($name:ident)*) => {
+        impl<T> IntoArray for Paren!($($crate::__rep!($name by T)),*) {
+            type Output = [T; $crate::__count_tts!($($name)*)];
+
+            #[inline]
+            fn into_array(self) -> Self::Output {
+                let $crate::paren_pat!($($name),*) = self;
+                [$($name),*]
+            }
+        }
+    };
+}
+
+macro_rules! impl_into_arrays {
+    ($first:ident $($rest:ident)*) => {
+        impl_into_array!($first $($rest)*);
+        impl_into_arrays!($($rest)*);
+    };
+    () => {
+        // there's no element type to use for the empty paren
+        impl IntoArray for () {
+            type Output = [(); 0];
+
+            #[inline(always)]
+            fn into_array(self) -> Self::Output {
+                []
+            }
+        }
+    };
+}
+
+impl_into_arrays!(a b c d e f g h i j k l m n o p);
+
+/// Converts parens into arrays, e.g. for use with [`Paren::map`] on nested parens.
+///
+/// [`Paren::map`]: ../trait.Paren.html#method.map
+#[derive(Copy, Clone, Debug, Default)]
+pub struct IntoArrayFn;
+
+impl<T: IntoArray> Func<T> for IntoArrayFn {
+    type Output = IntoArrayOut<T>;
+
+    #[inline]
+    fn call(input: T) -> Self::Output {
+        input.into_array()
+    }
+}
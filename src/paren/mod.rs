@@ -4,7 +4,9 @@ pub mod ops;
 mod macros;
 
 use crate::ident::{TypeEqFn, TypeId};
+use array::{IntoArray, IntoArrayOut};
 use ops::*;
+use std::cmp::Ordering;
 
 pub mod prelude {
     pub use super::Paren;
@@ -226,6 +228,196 @@ pub trait Paren: Sized {
     {
         All::all(self)
     }
+
+    /// Returns the minimum element of a non-empty paren whose elements all have the same type.
+    ///
+    /// Note that parens with elements that implement `Ord` also implement `Ord` themselves, so
+    /// this may need to be called as `Paren::min(p)` to avoid ambiguity with `Ord::min`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// let p = paren!(5u64, 3, 7, 4);
+    /// assert_eq!(Paren::min(p), 3u64);
+    /// assert_eq!(Paren::min(paren!(2.5, 1.0, -1.0)), -1.0);
+    /// assert_eq!(Paren::min(paren!('b', 'a', 'c')), 'a');
+    ///
+    /// // numbers of different types can be folded with promotion
+    /// use typing::func::ops::PromoteMinFn;
+    /// let p = paren!(5u64, 3u8, 7u32, 4u16);
+    /// assert_eq!(p.fold::<PromoteMinFn, _>(6u8), 3u64);
+    /// ```
+    #[inline]
+    fn min(self) -> MinOut<Self>
+    where
+        Self: Min,
+    {
+        Min::min(self)
+    }
+
+    /// Returns the maximum element of a non-empty paren whose elements all have the same type.
+    ///
+    /// Note that parens with elements that implement `Ord` also implement `Ord` themselves, so
+    /// this may need to be called as `Paren::max(p)` to avoid ambiguity with `Ord::max`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// let p = paren!(5u64, 3, 7, 4);
+    /// assert_eq!(Paren::max(p), 7u64);
+    /// assert_eq!(Paren::max(paren!(2.5, 1.0, -1.0)), 2.5);
+    /// assert_eq!(Paren::max(paren!('b', 'a', 'c')), 'c');
+    ///
+    /// // numbers of different types can be folded with promotion
+    /// use typing::func::ops::PromoteMaxFn;
+    /// let p = paren!(5u64, 3u8, 7u32, 4u16);
+    /// assert_eq!(p.fold::<PromoteMaxFn, _>(6u8), 7u64);
+    /// ```
+    #[inline]
+    fn max(self) -> MaxOut<Self>
+    where
+        Self: Max,
+    {
+        Max::max(self)
+    }
+
+    /// Returns the sum of all elements of a non-empty paren. Elements may be any mix of primitive
+    /// numeric types, and are promoted to a common type at each step as defined by
+    /// [`Promote`](../num/trait.Promote.html).
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// assert_eq!(paren!(1u8, 2u16, 3u32).sum(), 6u32);
+    /// assert_eq!(paren!(1u8, -2i8).sum(), -1i16);
+    /// assert_eq!(paren!(1u8, 2u32, 3.5f32).sum(), 6.5f64);
+    ///
+    /// // the underlying functor can also be used with an explicit initial value
+    /// use typing::func::ops::PromoteAddFn;
+    /// let p = paren!(1u8, 2u32, 3.0f32);
+    /// assert_eq!(p.fold::<PromoteAddFn, _>(0u8), 6.0f64);
+    /// ```
+    #[inline]
+    fn sum(self) -> SumOut<Self>
+    where
+        Self: Sum,
+    {
+        Sum::sum(self)
+    }
+
+    /// Returns the product of all elements of a non-empty paren, with the same promotion rules as
+    /// [`sum`](#method.sum).
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// assert_eq!(paren!(2u8, 3u16, 4i8).product(), 24i32);
+    /// assert_eq!(paren!(2.5f32, 2u8).product(), 5.0f32);
+    /// ```
+    #[inline]
+    fn product(self) -> ProductOut<Self>
+    where
+        Self: Product,
+    {
+        Product::product(self)
+    }
+
+    /// Returns `true` if every element is less than or equal to the next one, where each pair of
+    /// neighbouring elements only needs to be comparable via `PartialOrd`.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// assert!(paren!(1, 2, 2, 3).is_sorted());
+    /// assert!(!paren!(1.0, 0.5, 2.0).is_sorted());
+    /// assert!(paren!().is_sorted());
+    /// ```
+    #[inline]
+    #[allow(clippy::wrong_self_convention)]
+    fn is_sorted(self) -> bool
+    where
+        Self: IsSorted,
+    {
+        IsSorted::is_sorted(self)
+    }
+
+    /// Get a reference to the value for a key in a labelled paren, i.e. a paren of
+    /// `(key, value)` pairs. If a key occurs more than once the first value is used, and missing
+    /// keys are a compile-time error.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use typing::tags::{X, Y, Z};
+    ///
+    /// let p = paren!((X, 1.5), (Y, "foo"), (Z, 'z'), (X, 2.0));
+    /// assert_eq!(p.lookup(X), &1.5);
+    /// assert_eq!(p.lookup(Y), &"foo");
+    /// assert_eq!(p.lookup(Z), &'z');
+    /// ```
+    #[inline]
+    fn lookup<K>(&self, _: K) -> &LookupOut<Self, K>
+    where
+        Self: Lookup<K>,
+    {
+        Lookup::lookup(self)
+    }
+
+    /// Convert a paren where every element has the same type into an array.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use typing::paren::array::IntoArrayFn;
+    ///
+    /// assert_eq!(paren!(1, 2, 3).into_array(), [1, 2, 3]);
+    /// assert_eq!(paren!().into_array(), [(); 0]);
+    ///
+    /// // nested parens can be converted by mapping over them first
+    /// let p = paren!(paren!('a', 'b'), paren!('c', 'd'));
+    /// assert_eq!(p.map::<IntoArrayFn>().into_array(), [['a', 'b'], ['c', 'd']]);
+    /// ```
+    #[inline]
+    fn into_array(self) -> IntoArrayOut<Self>
+    where
+        Self: IntoArray,
+    {
+        IntoArray::into_array(self)
+    }
+
+    /// Lexicographically compare two parens for equality, where elements in the same position
+    /// need only be comparable via `PartialEq` rather than being the same type.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// let a = paren!("foo", 1, 'c');
+    /// assert!(a.lex_eq(&paren!(String::from("foo"), 1, 'c')));
+    /// assert!(!a.lex_eq(&paren!(String::from("bar"), 1, 'c')));
+    /// assert!(!a.lex_eq(&paren!("foo", 1)));
+    /// ```
+    #[inline]
+    fn lex_eq<B>(&self, other: &B) -> bool
+    where
+        Self: LexEq<B>,
+    {
+        LexEq::lex_eq(self, other)
+    }
+
+    /// Lexicographically compare two parens, where elements in the same position need only be
+    /// comparable via `PartialOrd` rather than being the same type. A paren that is a prefix of
+    /// another compares as less.
+    /// # Examples
+    /// ```
+    /// use typing::prelude::*;
+    /// use std::cmp::Ordering;
+    ///
+    /// let a = paren!(String::from("foo"), 1, 'c');
+    /// let b = paren!(String::from("foo"), 2, 'a');
+    /// assert_eq!(a.lex_partial_cmp(&b), Some(Ordering::Less));
+    /// assert_eq!(b.lex_partial_cmp(&a), Some(Ordering::Greater));
+    /// assert_eq!(a.lex_partial_cmp(&paren!(String::from("foo"), 1)), Some(Ordering::Greater));
+    /// assert_eq!(paren!(1.0).lex_partial_cmp(&paren!(std::f64::NAN)), None);
+    /// ```
+    #[inline]
+    fn lex_partial_cmp<B>(&self, other: &B) -> Option<Ordering>
+    where
+        Self: LexPartialOrd<B>,
+    {
+        LexPartialOrd::lex_partial_cmp(self, other)
+    }
 }
 
 impl<A, B: Paren> Paren for (A, B) {
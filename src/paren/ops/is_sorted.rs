@@ -0,0 +1,40 @@
+use crate::func::Func;
+use crate::paren::ops::Fold;
+
+// the accumulator is a tuple of whether the elements seen so far are sorted and the last element
+pub struct SortedStep;
+impl<P, A> Func<((bool, P), A)> for SortedStep
+where
+    P: PartialOrd<A>,
+{
+    type Output = (bool, A);
+
+    #[inline]
+    fn call(((sorted, p), a): ((bool, P), A)) -> Self::Output {
+        (sorted && p <= a, a)
+    }
+}
+
+// takes self by value like `Iterator::is_sorted` since it's built on top of `Fold`
+#[allow(clippy::wrong_self_convention)]
+pub trait IsSorted {
+    fn is_sorted(self) -> bool;
+}
+
+impl<A, B, P> IsSorted for (A, B)
+where
+    B: Fold<SortedStep, (bool, A), Output = (bool, P)>,
+{
+    #[inline]
+    fn is_sorted(self) -> bool {
+        let (a, b) = self;
+        b.fold((true, a)).0
+    }
+}
+
+impl IsSorted for () {
+    #[inline(always)]
+    fn is_sorted(self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,79 @@
+use std::cmp::Ordering;
+
+/// Lexicographic equality between parens with element-wise comparable types.
+pub trait LexEq<B> {
+    fn lex_eq(&self, other: &B) -> bool;
+}
+
+impl<A1, A2, B1, B2> LexEq<(B1, B2)> for (A1, A2)
+where
+    A1: PartialEq<B1>,
+    A2: LexEq<B2>,
+{
+    #[inline]
+    fn lex_eq(&self, other: &(B1, B2)) -> bool {
+        self.0 == other.0 && self.1.lex_eq(&other.1)
+    }
+}
+
+impl<B1, B2> LexEq<(B1, B2)> for () {
+    #[inline(always)]
+    fn lex_eq(&self, _: &(B1, B2)) -> bool {
+        false
+    }
+}
+
+impl<A1, A2> LexEq<()> for (A1, A2) {
+    #[inline(always)]
+    fn lex_eq(&self, _: &()) -> bool {
+        false
+    }
+}
+
+impl LexEq<()> for () {
+    #[inline(always)]
+    fn lex_eq(&self, _: &()) -> bool {
+        true
+    }
+}
+
+/// Lexicographic ordering between parens with element-wise comparable types. Shorter parens
+/// compare less than longer ones with the same prefix.
+pub trait LexPartialOrd<B>: LexEq<B> {
+    fn lex_partial_cmp(&self, other: &B) -> Option<Ordering>;
+}
+
+impl<A1, A2, B1, B2> LexPartialOrd<(B1, B2)> for (A1, A2)
+where
+    A1: PartialOrd<B1>,
+    A2: LexPartialOrd<B2>,
+{
+    #[inline]
+    fn lex_partial_cmp(&self, other: &(B1, B2)) -> Option<Ordering> {
+        match self.0.partial_cmp(&other.0) {
+            Some(Ordering::Equal) => self.1.lex_partial_cmp(&other.1),
+            ord => ord,
+        }
+    }
+}
+
+impl<B1, B2> LexPartialOrd<(B1, B2)> for () {
+    #[inline(always)]
+    fn lex_partial_cmp(&self, _: &(B1, B2)) -> Option<Ordering> {
+        Some(Ordering::Less)
+    }
+}
+
+impl<A1, A2> LexPartialOrd<()> for (A1, A2) {
+    #[inline(always)]
+    fn lex_partial_cmp(&self, _: &()) -> Option<Ordering> {
+        Some(Ordering::Greater)
+    }
+}
+
+impl LexPartialOrd<()> for () {
+    #[inline(always)]
+    fn lex_partial_cmp(&self, _: &()) -> Option<Ordering> {
+        Some(Ordering::Equal)
+    }
+}
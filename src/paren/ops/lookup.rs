@@ -0,0 +1,66 @@
+use crate::boolean::{False, True};
+use crate::ident::{IsEq, TypeEq};
+
+/// Lookup of values by key in a labelled paren, i.e. a paren of `(key, value)` pairs where each
+/// key implements [`TypeId`](../../ident/trait.TypeId.html).
+pub trait Lookup<Key> {
+    type Output;
+
+    fn lookup(&self) -> &Self::Output;
+}
+
+// like Filter, we first check whether the key of the next element matches and then either return
+// its value or continue in Lookup2
+#[doc(hidden)]
+pub trait Lookup2<Key, Marker> {
+    type Output;
+
+    fn lookup2(&self) -> &Self::Output;
+}
+
+pub type LookupOut<T, Key> = <T as Lookup<Key>>::Output;
+type LookupOut2<T, Key, K> = <T as Lookup2<Key, IsEq<K, Key>>>::Output;
+
+impl<Key, K, V, B> Lookup<Key> for ((K, V), B)
+where
+    K: TypeEq<Key>,
+    Self: Lookup2<Key, IsEq<K, Key>>,
+{
+    type Output = LookupOut2<Self, Key, K>;
+
+    #[inline(always)]
+    fn lookup(&self) -> &Self::Output {
+        self.lookup2()
+    }
+}
+
+impl<Key, K, V, B> Lookup2<Key, True> for ((K, V), B) {
+    type Output = V;
+
+    #[inline]
+    fn lookup2(&self) -> &Self::Output {
+        &(self.0).1
+    }
+}
+
+impl<Key, K, V, B> Lookup2<Key, False> for ((K, V), B)
+where
+    B: Lookup<Key>,
+{
+    type Output = LookupOut<B, Key>;
+
+    #[inline]
+    fn lookup2(&self) -> &Self::Output {
+        self.1.lookup()
+    }
+}
+
+// e.g. for chaining environments without copying them
+impl<Key, T: Lookup<Key>> Lookup<Key> for &T {
+    type Output = T::Output;
+
+    #[inline(always)]
+    fn lookup(&self) -> &Self::Output {
+        (**self).lookup()
+    }
+}
@@ -0,0 +1,40 @@
+use crate::func::ops::{MaxFn, MinFn};
+use crate::paren::ops::{Fold, FoldOut};
+
+pub trait Min {
+    type Output;
+    fn min(self) -> Self::Output;
+}
+
+impl<A, B> Min for (A, B)
+where
+    B: Fold<MinFn, A>,
+{
+    type Output = FoldOut<MinFn, B, A>;
+    #[inline]
+    fn min(self) -> Self::Output {
+        let (a, b) = self;
+        b.fold(a)
+    }
+}
+
+pub type MinOut<T> = <T as Min>::Output;
+
+pub trait Max {
+    type Output;
+    fn max(self) -> Self::Output;
+}
+
+impl<A, B> Max for (A, B)
+where
+    B: Fold<MaxFn, A>,
+{
+    type Output = FoldOut<MaxFn, B, A>;
+    #[inline]
+    fn max(self) -> Self::Output {
+        let (a, b) = self;
+        b.fold(a)
+    }
+}
+
+pub type MaxOut<T> = <T as Max>::Output;
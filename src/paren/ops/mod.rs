@@ -3,10 +3,15 @@ mod fill;
 mod filter;
 mod filter_map;
 mod fold;
+mod is_sorted;
+mod lex_cmp;
+mod lookup;
 mod map;
+mod min_max;
 mod reverse;
 mod rfold;
 mod scan;
+mod sum_product;
 mod zip;
 mod zip_with;
 
@@ -15,9 +20,14 @@ pub use fill::Fill;
 pub use filter::{Filter, FilterOut};
 pub use filter_map::{FilterMap, FilterMapOut};
 pub use fold::{Fold, FoldOut};
+pub use is_sorted::IsSorted;
+pub use lex_cmp::{LexEq, LexPartialOrd};
+pub use lookup::{Lookup, LookupOut};
 pub use map::{Map, MapOut};
+pub use min_max::{Max, MaxOut, Min, MinOut};
 pub use reverse::{Reverse, ReverseOut};
 pub use rfold::{RFold, RFoldOut};
 pub use scan::{Scan, ScanOut};
+pub use sum_product::{Product, ProductOut, Sum, SumOut};
 pub use zip::{Zip, ZipOut};
 pub use zip_with::{ZipWith, ZipWithOut};
@@ -0,0 +1,40 @@
+use crate::func::ops::{PromoteAddFn, PromoteMulFn};
+use crate::paren::ops::{Fold, FoldOut};
+
+pub trait Sum {
+    type Output;
+    fn sum(self) -> Self::Output;
+}
+
+impl<A, B> Sum for (A, B)
+where
+    B: Fold<PromoteAddFn, A>,
+{
+    type Output = FoldOut<PromoteAddFn, B, A>;
+    #[inline]
+    fn sum(self) -> Self::Output {
+        let (a, b) = self;
+        b.fold(a)
+    }
+}
+
+pub type SumOut<T> = <T as Sum>::Output;
+
+pub trait Product {
+    type Output;
+    fn product(self) -> Self::Output;
+}
+
+impl<A, B> Product for (A, B)
+where
+    B: Fold<PromoteMulFn, A>,
+{
+    type Output = FoldOut<PromoteMulFn, B, A>;
+    #[inline]
+    fn product(self) -> Self::Output {
+        let (a, b) = self;
+        b.fold(a)
+    }
+}
+
+pub type ProductOut<T> = <T as Product>::Output;